@@ -1,23 +1,93 @@
 use super::object::Object;
-use std::collections::HashMap;
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
 
+/// A scope is shared between the code running in it and every closure created
+/// in it, so it's reference-counted.
+pub type SharedEnvironment = Rc<RefCell<Environment>>;
+
+/// Keys are owned rather than borrowed from the source, so a scope doesn't
+/// depend on the lifetime of the input it was populated from.
 #[derive(Default)]
-pub struct Environment<'ident> {
-  records: HashMap<&'ident str, Object>,
+pub struct Environment {
+  records: HashMap<String, Object>,
+  /// The names of `records` bound by `const`.
+  constants: HashSet<String>,
+  /// The enclosing scope, `None` for the global scope.
+  outer: Option<SharedEnvironment>,
 }
 
-impl<'ident> Environment<'ident> {
+impl Environment {
   pub fn new() -> Self {
     Environment {
       records: HashMap::new(),
+      constants: HashSet::new(),
+      outer: None,
+    }
+  }
+
+  /// Create a scope nested in `outer`, e.g. a block or a function body.
+  pub fn enclose(outer: SharedEnvironment) -> Self {
+    Environment {
+      records: HashMap::new(),
+      constants: HashSet::new(),
+      outer: Some(outer),
     }
   }
 
-  pub fn get(&self, name: &'ident str) -> Option<Object> {
-    self.records.get(name).map(|val| val.to_owned())
+  pub fn into_shared(self) -> SharedEnvironment {
+    Rc::new(RefCell::new(self))
   }
 
-  pub fn set(&mut self, name: &'ident str, value: Object) -> Option<Object> {
-    self.records.insert(name, value)
+  /// Resolve `name` in this scope first, then walk outward through the
+  /// enclosing scopes.
+  pub fn get(&self, name: &str) -> Option<Object> {
+    match self.records.get(name) {
+      Some(val) => Some(val.to_owned()),
+      None => self.outer.as_ref()?.borrow().get(name),
+    }
+  }
+
+  /// Bind `name` in this scope, shadowing any binding of an enclosing scope.
+  /// A constant of this scope stays constant, since constants can't be
+  /// redeclared in their scope.
+  pub fn set(&mut self, name: &str, value: Object) -> Option<Object> {
+    self.records.insert(name.to_owned(), value)
+  }
+
+  /// Like `set`, for a binding which can't be assigned to afterwards.
+  pub fn set_const(&mut self, name: &str, value: Object) -> Option<Object> {
+    let previous = self.set(name, value);
+    self.constants.insert(name.to_owned());
+    previous
+  }
+
+  /// Whether this scope, rather than an enclosing one, binds `name` as a
+  /// constant.
+  pub fn binds_const(&self, name: &str) -> bool {
+    self.constants.contains(name)
+  }
+
+  /// Whether the nearest binding of `name` is a constant.
+  pub fn is_const(&self, name: &str) -> bool {
+    if self.records.contains_key(name) {
+      self.constants.contains(name)
+    } else {
+      self
+        .outer
+        .as_ref()
+        .is_some_and(|outer| outer.borrow().is_const(name))
+    }
+  }
+
+  /// Call `f` with the nearest binding of `name`, to change its value in
+  /// place, and return what it returns. `f` isn't called if `name` isn't bound
+  /// in any scope, which returns `None`.
+  pub fn with_binding<T>(&mut self, name: &str, f: impl FnOnce(&mut Object) -> T) -> Option<T> {
+    match self.records.get_mut(name) {
+      Some(record) => Some(f(record)),
+      None => self.outer.as_ref()?.borrow_mut().with_binding(name, f),
+    }
   }
 }
@@ -0,0 +1,55 @@
+use pratt_parsing::diagnostic::{Diagnostic, Label};
+use pratt_parsing::span::Span;
+use std::fmt;
+
+/// An error which stops the evaluation of a program.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RuntimeError {
+  message: String,
+  /// The innermost expression or statement which failed. It's `None` for a
+  /// `break` or `continue` outside of a loop, which isn't caught by anything.
+  span: Option<Span>,
+}
+
+impl RuntimeError {
+  pub fn new(message: String) -> Self {
+    RuntimeError {
+      message,
+      span: None,
+    }
+  }
+
+  /// Locate the error at `span`, unless it's located already.
+  pub(crate) fn at(mut self, span: Span) -> Self {
+    self.span.get_or_insert(span);
+    self
+  }
+
+  /// Forget where the error occurred, see `Evaluator::apply_function`.
+  pub(crate) fn unlocated(mut self) -> Self {
+    self.span = None;
+    self
+  }
+
+  pub fn message(&self) -> &str {
+    &self.message
+  }
+
+  pub fn span(&self) -> Option<Span> {
+    self.span
+  }
+
+  pub fn to_diagnostic(&self) -> Diagnostic {
+    let diagnostic = Diagnostic::error(&self.message);
+    match self.span {
+      Some(span) => diagnostic.with_label(Label::primary(span, "")),
+      None => diagnostic,
+    }
+  }
+}
+
+impl fmt::Display for RuntimeError {
+  fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+    write!(formatter, "{}", self.message)
+  }
+}
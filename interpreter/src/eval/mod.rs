@@ -1,79 +1,159 @@
+mod error;
 #[cfg(test)]
 mod test;
 
-use super::environment::Environment;
-use super::object::Object;
+pub use error::RuntimeError;
+
+use super::environment::{Environment, SharedEnvironment};
+pub use super::object::ArithmeticMode;
+
+use super::object::{symbol, Function, HashKey, Object};
 use pratt_parsing::ast;
+use pratt_parsing::ast::Spanned;
+use pratt_parsing::span::Span;
+use std::collections::btree_map::Entry;
+use std::collections::BTreeMap;
+use std::ops::ControlFlow;
+use std::rc::Rc;
 
 #[derive(Default)]
-pub struct Evaluator<'ident> {
-  environment: Environment<'ident>,
+pub struct Evaluator {
+  environment: SharedEnvironment,
+  arithmetic_mode: ArithmeticMode,
 }
 
-impl<'ident> Evaluator<'ident> {
+impl Evaluator {
   pub fn new() -> Self {
     Evaluator {
-      environment: Environment::new(),
+      environment: Environment::new().into_shared(),
+      arithmetic_mode: ArithmeticMode::default(),
     }
   }
 
-  pub fn eval(&mut self, stmts: Vec<ast::Statement<'ident>>) -> Result<Object, String> {
-    match self.eval_block_stmt(stmts) {
+  /// Use `mode` for integer arithmetic, instead of reporting overflows as
+  /// errors.
+  pub fn with_arithmetic_mode(mut self, mode: ArithmeticMode) -> Self {
+    self.arithmetic_mode = mode;
+    self
+  }
+
+  pub fn eval(&mut self, stmts: ast::Program) -> Result<Object, RuntimeError> {
+    match self.eval_stmts(stmts) {
       Some(result) => match result {
         Object::ReturnValue(val) => Ok(*val), // unwrap value for better DX
         Object::Error(error) => Err(error),
+        signal @ (Object::Break | Object::Continue) => {
+          Err(RuntimeError::new(Self::loop_control_error(&signal)))
+        }
         val => Ok(val),
       },
       None => Ok(Object::None),
     }
   }
 
-  fn eval_stmt(&mut self, stmt: ast::Statement<'ident>) -> Option<Object> {
-    match stmt {
+  fn eval_stmt(&mut self, stmt: Spanned<ast::Statement>) -> Option<Object> {
+    let span = stmt.span;
+    let object = match stmt.node {
       ast::Statement::Expr(expr) => self.eval_expr(expr),
-      ast::Statement::Let(ident, expr) => self.eval_let_stmt(ident, expr),
+      ast::Statement::Let(ident, expr) => self.eval_let_stmt(ident.node, expr, false),
+      ast::Statement::Const(ident, expr) => self.eval_let_stmt(ident.node, expr, true),
       ast::Statement::Return(expr) => self.eval_return_stmt(expr),
-    }
+      ast::Statement::Break => Some(Object::Break),
+      ast::Statement::Continue => Some(Object::Continue),
+      ast::Statement::Error => Some(Self::error(
+        "Couldn't evaluate a malformed statement".to_string(),
+      )),
+    };
+    object.map(|object| Self::locate(object, span))
+  }
+
+  /// Evaluate a `{ ... }` block in a new scope nested in the current one.
+  fn eval_block_stmt(&mut self, block_stmts: ast::BlockStatement) -> Option<Object> {
+    let scope = Environment::enclose(Rc::clone(&self.environment));
+    self.eval_in_scope(scope.into_shared(), block_stmts)
+  }
+
+  /// Evaluate statements with `scope` as the current environment, and restore
+  /// the previous one afterwards.
+  fn eval_in_scope(
+    &mut self,
+    scope: SharedEnvironment,
+    stmts: ast::BlockStatement,
+  ) -> Option<Object> {
+    let outer = std::mem::replace(&mut self.environment, scope);
+    let result = self.eval_stmts(stmts);
+    self.environment = outer;
+    result
   }
 
-  fn eval_block_stmt(&mut self, block_stmts: Vec<ast::Statement<'ident>>) -> Option<Object> {
+  /// Evaluate statements in the current environment.
+  fn eval_stmts(&mut self, stmts: ast::BlockStatement) -> Option<Object> {
     let mut result: Option<Object> = None;
-    for stmt in block_stmts {
-      match self.eval_stmt(stmt)? {
-        Object::ReturnValue(val) => return Some(Object::ReturnValue(val)),
-        Object::Error(error) => return Some(Self::error(error)),
-        obj => result = Some(obj),
+    for stmt in stmts {
+      match self.eval_stmt(stmt) {
+        Some(obj) if Self::is_unwinding(&obj) => return Some(obj),
+        obj => result = obj,
       }
     }
     result
   }
 
-  fn eval_expr(&mut self, expr: ast::Expr<'ident>) -> Option<Object> {
+  fn eval_expr(&mut self, expr: Spanned<ast::Expr>) -> Option<Object> {
     use ast::Expr;
-    match expr {
+    let span = expr.span;
+    let object = match expr.node {
       Expr::Literal(literal) => self.eval_literal(literal),
       Expr::Ident(ident) => self.eval_ident(ident),
       Expr::Prefix(prefix, expr) => self.eval_prefix_expr(prefix, *expr),
+      Expr::Postfix(expr, postfix) => self.eval_postfix_expr(*expr, postfix),
       Expr::Infix(left, infix, right) => self.eval_infix_expr(*left, infix, *right),
       Expr::If {
         condition,
         consequence,
         alternative,
       } => self.eval_if_expr(*condition, consequence, alternative),
-      _ => None,
-    }
+      Expr::While { condition, body } => self.eval_while_expr(*condition, body),
+      Expr::For {
+        binding,
+        iterable,
+        body,
+      } => self.eval_for_expr(binding, *iterable, body),
+      Expr::Match { subject, arms } => self.eval_match_expr(*subject, arms),
+      Expr::Assign {
+        target,
+        operator,
+        value,
+      } => self.eval_assign_expr(*target, operator, *value),
+      Expr::Function { params, body } => Some(Object::Function(Function {
+        params: params.into_iter().map(|param| param.node).collect(),
+        body,
+        env: Rc::clone(&self.environment),
+      })),
+      Expr::Call {
+        function,
+        arguments,
+      } => self.eval_call_expr(*function, arguments),
+      Expr::Array(elements) => self.eval_array_literal(elements),
+      Expr::Hash(pairs) => self.eval_hash_literal(pairs),
+      Expr::Index { left, index } => self.eval_index_expr(*left, *index),
+    };
+    object.map(|object| Self::locate(object, span))
   }
 
   fn eval_literal(&self, literal: ast::Literal) -> Option<Object> {
     match literal {
       ast::Literal::Int(val) => Some(Object::Int(val)),
+      #[cfg(feature = "bigint")]
+      ast::Literal::BigInt(val) => Some(Object::from_bigint(val)),
+      ast::Literal::Float(val) => Some(Object::Float(val)),
       ast::Literal::Bool(val) => Some(Object::Bool(val)),
+      ast::Literal::Str(val) => Some(Object::Str(val)),
     }
   }
 
-  fn eval_ident(&self, ident: ast::Ident<'ident>) -> Option<Object> {
+  fn eval_ident(&self, ident: ast::Ident) -> Option<Object> {
     let ast::Ident(ident) = ident;
-    if let Some(value) = self.environment.get(ident) {
+    if let Some(value) = self.environment.borrow().get(&ident) {
       Some(value)
     } else {
       Some(Self::error(format!("Identifier not found: {}", ident)))
@@ -82,21 +162,40 @@ impl<'ident> Evaluator<'ident> {
 }
 
 // errors
-impl Evaluator<'_> {
+impl Evaluator {
   fn error(message: String) -> Object {
-    Object::Error(message)
+    Object::error(message)
+  }
+
+  /// Locate an error at the expression or statement it stops, unless a nested
+  /// one has been located already.
+  fn locate(object: Object, span: Span) -> Object {
+    match object {
+      Object::Error(error) => Object::Error(error.at(span)),
+      object => object,
+    }
+  }
+
+  /// Errors, `return`s, `break`s and `continue`s stop the evaluation of the
+  /// enclosing expressions and statements, so they're passed on as they are.
+  fn is_unwinding(object: &Object) -> bool {
+    matches!(
+      object,
+      Object::Error(_) | Object::ReturnValue(_) | Object::Break | Object::Continue
+    )
   }
 
-  fn is_error(object: &Object) -> bool {
-    matches!(object, Object::Error(_))
+  /// A `break` or `continue` which isn't caught by a loop.
+  fn loop_control_error(signal: &Object) -> String {
+    format!("Couldn't {} outside of a loop", signal)
   }
 }
 
 // eval_x_stmt
-impl<'ident> Evaluator<'ident> {
-  fn eval_return_stmt(&mut self, expr: ast::Expr<'ident>) -> Option<Object> {
+impl Evaluator {
+  fn eval_return_stmt(&mut self, expr: Spanned<ast::Expr>) -> Option<Object> {
     self.eval_expr(expr).map(|value| {
-      if Self::is_error(&value) {
+      if Self::is_unwinding(&value) {
         value
       } else {
         Object::ReturnValue(Box::new(value))
@@ -106,90 +205,684 @@ impl<'ident> Evaluator<'ident> {
 
   fn eval_let_stmt(
     &mut self,
-    ident: ast::Ident<'ident>,
-    expr: ast::Expr<'ident>,
+    ident: ast::Ident,
+    expr: Spanned<ast::Expr>,
+    is_const: bool,
   ) -> Option<Object> {
     let value = self.eval_expr(expr)?;
-    if Self::is_error(&value) {
+    if Self::is_unwinding(&value) {
       return Some(value);
     }
 
     use ast::Ident;
     let Ident(ident) = ident;
-    self.environment.set(ident, value.clone());
+    let mut environment = self.environment.borrow_mut();
+    // the `Checker` reports this before evaluation already
+    if environment.binds_const(&ident) {
+      return Some(Self::error(format!(
+        "Couldn't redeclare a constant: {}",
+        ident
+      )));
+    }
+    if is_const {
+      environment.set_const(&ident, value.clone());
+    } else {
+      environment.set(&ident, value.clone());
+    }
     Some(value)
   }
 }
 
 // eval_x_expr
-impl<'ident> Evaluator<'ident> {
-  fn eval_prefix_expr(&mut self, prefix: ast::Prefix, expr: ast::Expr<'ident>) -> Option<Object> {
+impl Evaluator {
+  fn eval_prefix_expr(&mut self, prefix: ast::Prefix, expr: Spanned<ast::Expr>) -> Option<Object> {
     use ast::Prefix;
 
+    let arithmetic_mode = self.arithmetic_mode;
     self.eval_expr(expr).map(|result| match prefix {
+      _ if Self::is_unwinding(&result) => result,
       Prefix::Bang => match result {
         Object::Bool(false) => Object::Bool(true),
         Object::Int(val) => Object::Bool(val == 0),
+        Object::Float(val) => Object::Bool(val == 0.0),
         _ => Object::Bool(false),
       },
 
       Prefix::Minus => {
         let result = match result {
-          Object::Int(val) => Object::Int(-val),
+          Object::Int(val) => arithmetic_mode.negate(val),
+          #[cfg(feature = "bigint")]
+          Object::BigInt(val) => Object::from_bigint(-val),
+          Object::Float(val) => Object::Float(-val),
           _ => Self::error(format!("Illegal syntax: -{}", result)),
         };
         result
       }
 
+      Prefix::BitNot => match result {
+        Object::Int(val) => Object::Int(!val),
+        #[cfg(feature = "bigint")]
+        Object::BigInt(val) => Object::from_bigint(!val),
+        _ => Self::error(format!("Illegal syntax: ~{}", result)),
+      },
+
       Prefix::Plus => match result {
-        Object::Int(_) => result,
+        Object::Int(_) | Object::Float(_) => result,
+        #[cfg(feature = "bigint")]
+        Object::BigInt(_) => result,
         _ => Self::error(format!("Illegal syntax: +{}", result)),
       },
     })
   }
 
+  fn eval_postfix_expr(
+    &mut self,
+    expr: Spanned<ast::Expr>,
+    postfix: ast::Postfix,
+  ) -> Option<Object> {
+    use ast::Postfix;
+
+    let value = self.eval_expr(expr);
+    match postfix {
+      Postfix::Try => match value {
+        None | Some(Object::None) => Some(Object::ReturnValue(Box::new(Object::None))),
+        value => value,
+      },
+
+      Postfix::Factorial => value.map(|value| match value {
+        _ if Self::is_unwinding(&value) => value,
+        Object::Int(n) => self.arithmetic_mode.factorial(n),
+        #[cfg(feature = "bigint")]
+        Object::BigInt(n) => Self::error(format!("Factorial of a too large number: {}!", n)),
+        _ => Self::error(format!("Illegal syntax: {}!", value)),
+      }),
+    }
+  }
+
   fn eval_infix_expr(
     &mut self,
-    left_expr: ast::Expr<'ident>,
+    left_expr: Spanned<ast::Expr>,
     infix: ast::Infix,
-    right_expr: ast::Expr<'ident>,
+    right_expr: Spanned<ast::Expr>,
   ) -> Option<Object> {
-    let left = self.eval_expr(left_expr)?;
-    let right = self.eval_expr(right_expr)?;
+    use ast::Infix;
 
-    if Self::is_error(&left) {
+    let left = self.eval_expr(left_expr)?;
+    if Self::is_unwinding(&left) {
       return Some(left);
     }
-    if Self::is_error(&right) {
+    if let Infix::And | Infix::Or = infix {
+      return self.eval_logical_expr(left, infix, right_expr);
+    }
+    let right = self.eval_expr(right_expr)?;
+    if Self::is_unwinding(&right) {
       return Some(right);
     }
 
+    Some(self.eval_binary(left, infix, right))
+  }
+
+  /// Apply `infix`, which isn't a logical operator, to evaluated operands.
+  fn eval_binary(&self, left: Object, infix: ast::Infix, right: Object) -> Object {
     use ast::Infix;
-    let result = match infix {
-      Infix::Equal => Object::Bool(left == right),
-      Infix::NotEqual => Object::Bool(left != right),
-      Infix::LessThan => Object::Bool(left < right),
-      Infix::LessThanEqual => Object::Bool(left <= right),
-      Infix::GreaterThan => Object::Bool(left > right),
-      Infix::GreaterThanEqual => Object::Bool(left >= right),
-      Infix::Plus => left + right,
-      Infix::Minus => left - right,
-      Infix::Multiply => left * right,
-      Infix::Divide => left / right,
+
+    match infix {
+      Infix::Equal => Object::Bool(left.equals(&right)),
+      Infix::NotEqual => Object::Bool(!left.equals(&right)),
+      Infix::LessThan | Infix::LessThanEqual | Infix::GreaterThan | Infix::GreaterThanEqual => {
+        Self::compare(left, infix, right)
+      }
+      Infix::Plus
+      | Infix::Minus
+      | Infix::Multiply
+      | Infix::Divide
+      | Infix::Modulo
+      | Infix::Power => self.eval_arithmetic(left, infix, right),
+      Infix::BitAnd => left & right,
+      Infix::BitOr => left | right,
+      Infix::BitXor => left ^ right,
+      Infix::ShiftLeft => left << right,
+      Infix::ShiftRight => left >> right,
+      Infix::Range => left.range(right, false),
+      Infix::RangeInclusive => left.range(right, true),
+      Infix::And | Infix::Or => unreachable!("logical operators short-circuit"),
+    }
+  }
+
+  /// `&&` and `||` only evaluate their right operand if the left one doesn't
+  /// decide the result already. Operands follow the same truthiness rules as
+  /// conditions of `if`.
+  fn eval_logical_expr(
+    &mut self,
+    left: Object,
+    infix: ast::Infix,
+    right_expr: Spanned<ast::Expr>,
+  ) -> Option<Object> {
+    let left_val = match Self::condition(&left) {
+      Ok(val) => val,
+      Err(error) => return Some(error),
+    };
+    match (infix, left_val) {
+      (ast::Infix::And, false) | (ast::Infix::Or, true) => Some(Object::Bool(left_val)),
+      _ => {
+        let right = self.eval_expr(right_expr).unwrap_or(Object::None);
+        if Self::is_unwinding(&right) {
+          return Some(right);
+        }
+        Some(match Self::condition(&right) {
+          Ok(val) => Object::Bool(val),
+          Err(error) => error,
+        })
+      }
+    }
+  }
+
+  /// Whether `object` counts as true in a condition. Only bools and numbers,
+  /// where zero is false, can be used as conditions.
+  fn truthiness(object: &Object) -> Option<bool> {
+    match object {
+      Object::Bool(val) => Some(*val),
+      Object::Int(val) => Some(*val != 0),
+      // big integers are never zero
+      #[cfg(feature = "bigint")]
+      Object::BigInt(_) => Some(true),
+      Object::Float(val) => Some(*val != 0.0),
+      _ => None,
+    }
+  }
+
+  /// The truthiness of a condition, or an error if `object` can't be used as
+  /// one.
+  fn condition(object: &Object) -> Result<bool, Object> {
+    Self::truthiness(object)
+      .ok_or_else(|| Self::error(format!("Couldn't use {} as a condition", object)))
+  }
+
+  /// Integers follow the arithmetic mode of the evaluator, other operands are
+  /// handled by the operators of `Object`.
+  fn eval_arithmetic(&self, left: Object, infix: ast::Infix, right: Object) -> Object {
+    use ast::Infix;
+    match (left, right) {
+      (Object::Int(left_val), Object::Int(right_val)) => {
+        self.arithmetic_mode.apply(left_val, &infix, right_val)
+      }
+      (left, right) => match infix {
+        Infix::Plus => left + right,
+        Infix::Minus => left - right,
+        Infix::Multiply => left * right,
+        Infix::Modulo => left % right,
+        Infix::Power => left.pow(right),
+        _ => left / right,
+      },
+    }
+  }
+
+  /// See `Object::compare`, strings are ordered lexicographically.
+  fn compare(left: Object, infix: ast::Infix, right: Object) -> Object {
+    use ast::Infix;
+    let ordering = match left.compare(&right) {
+      Ok(ordering) => ordering,
+      Err(()) => {
+        return Self::error(format!(
+          "Couldn't perform operation: {} {} {}",
+          left,
+          symbol(&infix),
+          right
+        ))
+      }
     };
-    Some(result)
+    Object::Bool(ordering.is_some_and(|ordering| match infix {
+      Infix::LessThan => ordering.is_lt(),
+      Infix::LessThanEqual => ordering.is_le(),
+      Infix::GreaterThan => ordering.is_gt(),
+      _ => ordering.is_ge(),
+    }))
   }
 
   fn eval_if_expr(
     &mut self,
-    condition: ast::Expr<'ident>,
-    consequence: Vec<ast::Statement<'ident>>,
-    alternative: Option<Vec<ast::Statement<'ident>>>,
+    condition: Spanned<ast::Expr>,
+    consequence: ast::BlockStatement,
+    alternative: Option<ast::BlockStatement>,
   ) -> Option<Object> {
-    match self.eval_expr(condition)? {
-      Object::Bool(val) => self.eval_block_stmt(if val { consequence } else { alternative? }),
-      Object::Int(val) => self.eval_block_stmt(if val != 0 { consequence } else { alternative? }),
-      _ => None,
+    let condition = self.eval_expr(condition).unwrap_or(Object::None);
+    if Self::is_unwinding(&condition) {
+      return Some(condition);
+    }
+    match Self::condition(&condition) {
+      Ok(true) => self.eval_block_stmt(consequence),
+      Ok(false) => self.eval_block_stmt(alternative?),
+      Err(error) => Some(error),
+    }
+  }
+
+  /// A loop evaluates to no value. Its condition must be a bool or a number,
+  /// see `truthiness`.
+  fn eval_while_expr(
+    &mut self,
+    condition: Spanned<ast::Expr>,
+    body: ast::BlockStatement,
+  ) -> Option<Object> {
+    loop {
+      let value = self.eval_expr(condition.clone()).unwrap_or(Object::None);
+      if Self::is_unwinding(&value) {
+        return Some(value);
+      }
+      match Self::condition(&value) {
+        Ok(true) => {}
+        Ok(false) => return None,
+        Err(error) => return Some(error),
+      }
+
+      if let ControlFlow::Break(result) = Self::loop_control(self.eval_block_stmt(body.clone())) {
+        return result;
+      }
+    }
+  }
+
+  /// Each iteration runs in a new scope with the loop variables bound, see
+  /// `Iterable`.
+  fn eval_for_expr(
+    &mut self,
+    binding: ast::ForBinding,
+    iterable: Spanned<ast::Expr>,
+    body: ast::BlockStatement,
+  ) -> Option<Object> {
+    let iterable = self.eval_expr(iterable)?;
+    if Self::is_unwinding(&iterable) {
+      return Some(iterable);
+    }
+    let entries = match iterable.as_iterable() {
+      Some(entries) => entries,
+      None => return Some(Self::error(format!("Couldn't iterate over {}", iterable))),
+    };
+
+    for entry in entries.entries() {
+      let mut scope = Environment::enclose(Rc::clone(&self.environment));
+      match &binding {
+        ast::ForBinding::Item(name) => {
+          scope.set(&name.node.0, entries.item(entry));
+        }
+        ast::ForBinding::Entry(key, value) => {
+          scope.set(&key.node.0, entry.0);
+          scope.set(&value.node.0, entry.1);
+        }
+      }
+
+      let result = self.eval_in_scope(scope.into_shared(), body.clone());
+      if let ControlFlow::Break(result) = Self::loop_control(result) {
+        return result;
+      }
+    }
+    None
+  }
+
+  /// Whether a loop goes on after its body evaluated to `result`. `break`
+  /// leaves the loop, which evaluates to no value, and other unwinding objects
+  /// than `continue` are passed on.
+  fn loop_control(result: Option<Object>) -> ControlFlow<Option<Object>> {
+    match result {
+      Some(Object::Break) => ControlFlow::Break(None),
+      Some(Object::Continue) => ControlFlow::Continue(()),
+      Some(result) if Self::is_unwinding(&result) => ControlFlow::Break(Some(result)),
+      _ => ControlFlow::Continue(()),
+    }
+  }
+
+  /// The first arm whose pattern matches the subject and whose guard holds is
+  /// evaluated, in a new scope with the bindings of its pattern.
+  fn eval_match_expr(
+    &mut self,
+    subject: Spanned<ast::Expr>,
+    arms: Vec<ast::MatchArm>,
+  ) -> Option<Object> {
+    let subject = self.eval_expr(subject)?;
+    if Self::is_unwinding(&subject) {
+      return Some(subject);
+    }
+
+    for arm in arms {
+      let mut bindings = vec![];
+      if !self.match_pattern(&arm.pattern.node, &subject, &mut bindings) {
+        continue;
+      }
+      let mut scope = Environment::enclose(Rc::clone(&self.environment));
+      for (name, value) in bindings {
+        scope.set(name, value);
+      }
+
+      let outer = std::mem::replace(&mut self.environment, scope.into_shared());
+      let result = self.eval_match_arm(arm.guard, arm.body);
+      self.environment = outer;
+      if let ControlFlow::Break(result) = result {
+        return result;
+      }
+    }
+    Some(Self::error(format!("Couldn't match {}", subject)))
+  }
+
+  /// Evaluate the body of an arm whose pattern matched, unless its guard
+  /// doesn't hold and the next arm is tried.
+  fn eval_match_arm(
+    &mut self,
+    guard: Option<Spanned<ast::Expr>>,
+    body: ast::BlockStatement,
+  ) -> ControlFlow<Option<Object>> {
+    if let Some(guard) = guard {
+      let value = self.eval_expr(guard).unwrap_or(Object::None);
+      if Self::is_unwinding(&value) {
+        return ControlFlow::Break(Some(value));
+      }
+      match Self::condition(&value) {
+        Ok(true) => {}
+        Ok(false) => return ControlFlow::Continue(()),
+        Err(error) => return ControlFlow::Break(Some(error)),
+      }
+    }
+    ControlFlow::Break(self.eval_stmts(body))
+  }
+
+  /// Whether `pattern` matches `object`, collecting what its identifiers are
+  /// bound to in `bindings`. Literals match values they're `==` to.
+  fn match_pattern<'pattern>(
+    &self,
+    pattern: &'pattern ast::Pattern,
+    object: &Object,
+    bindings: &mut Vec<(&'pattern str, Object)>,
+  ) -> bool {
+    match (pattern, object) {
+      (ast::Pattern::Wildcard, _) => true,
+      (ast::Pattern::Binding(ast::Ident(name)), object) => {
+        bindings.push((name, object.clone()));
+        true
+      }
+      (ast::Pattern::Literal(literal), object) => self
+        .eval_literal(literal.clone())
+        .is_some_and(|literal| literal.equals(object)),
+      (ast::Pattern::Array { elements, rest }, Object::Array(array)) => {
+        let matches_len = match rest {
+          Some(_) => array.len() >= elements.len(),
+          None => array.len() == elements.len(),
+        };
+        matches_len
+          && elements
+            .iter()
+            .zip(array)
+            .all(|(element, object)| self.match_pattern(&element.node, object, bindings))
+          && rest.as_ref().is_none_or(|rest| {
+            let remaining = Object::Array(array[elements.len()..].to_vec());
+            self.match_pattern(&rest.node, &remaining, bindings)
+          })
+      }
+      (ast::Pattern::Hash(pairs), Object::Hash(hash)) => pairs.iter().all(|(key, pattern)| {
+        let value = self
+          .eval_literal(key.node.clone())
+          .and_then(|key| HashKey::from_object(&key))
+          .and_then(|key| hash.get(&key));
+        value.is_some_and(|value| self.match_pattern(&pattern.node, value, bindings))
+      }),
+      _ => false,
+    }
+  }
+
+  fn eval_call_expr(
+    &mut self,
+    function: Spanned<ast::Expr>,
+    arguments: Vec<Spanned<ast::Expr>>,
+  ) -> Option<Object> {
+    let function = self.eval_expr(function)?;
+    if Self::is_unwinding(&function) {
+      return Some(function);
+    }
+
+    let args = match self.eval_exprs(arguments)? {
+      Ok(args) => args,
+      Err(error) => return Some(error),
+    };
+
+    self.apply_function(function, args)
+  }
+
+  /// Evaluate expressions from left to right, stopping at the first error.
+  fn eval_exprs(&mut self, exprs: Vec<Spanned<ast::Expr>>) -> Option<Result<Vec<Object>, Object>> {
+    let mut values = vec![];
+    for expr in exprs {
+      let value = self.eval_expr(expr)?;
+      if Self::is_unwinding(&value) {
+        return Some(Err(value));
+      }
+      values.push(value);
+    }
+    Some(Ok(values))
+  }
+
+  fn eval_array_literal(&mut self, elements: Vec<Spanned<ast::Expr>>) -> Option<Object> {
+    Some(match self.eval_exprs(elements)? {
+      Ok(elements) => Object::Array(elements),
+      Err(error) => error,
+    })
+  }
+
+  fn eval_hash_literal(
+    &mut self,
+    pairs: Vec<(Spanned<ast::Expr>, Spanned<ast::Expr>)>,
+  ) -> Option<Object> {
+    let mut hash = BTreeMap::new();
+    for (key, value) in pairs {
+      let key = self.eval_expr(key)?;
+      if Self::is_unwinding(&key) {
+        return Some(key);
+      }
+      let key = match Self::hash_key(&key) {
+        Ok(key) => key,
+        Err(error) => return Some(error),
+      };
+      let value = self.eval_expr(value)?;
+      if Self::is_unwinding(&value) {
+        return Some(value);
+      }
+      hash.insert(key, value);
+    }
+    Some(Object::Hash(hash))
+  }
+
+  fn hash_key(object: &Object) -> Result<HashKey, Object> {
+    HashKey::from_object(object)
+      .ok_or_else(|| Self::error(format!("Unhashable hash key: {}", object)))
+  }
+
+  fn eval_index_expr(
+    &mut self,
+    left: Spanned<ast::Expr>,
+    index: Spanned<ast::Expr>,
+  ) -> Option<Object> {
+    let left = self.eval_expr(left)?;
+    if Self::is_unwinding(&left) {
+      return Some(left);
+    }
+    let index = self.eval_expr(index)?;
+    if Self::is_unwinding(&index) {
+      return Some(index);
+    }
+
+    Some(Self::index(left, index))
+  }
+
+  fn index(left: Object, index: Object) -> Object {
+    match (left, index) {
+      (Object::Array(mut elements), index) => match Self::array_position(&elements, &index) {
+        Ok(position) => elements.swap_remove(position),
+        Err(error) => error,
+      },
+      (Object::Hash(mut pairs), key) => match Self::hash_key(&key) {
+        Ok(hash_key) => pairs
+          .remove(&hash_key)
+          .unwrap_or_else(|| Self::error(format!("Key not found: {}", key))),
+        Err(error) => error,
+      },
+      (left, _) => Self::error(format!("Couldn't index into {}", left)),
+    }
+  }
+
+  /// Where `index` points to in `elements`. A negative index counts backwards
+  /// from the end of the array, `-1` being the last element.
+  fn array_position(elements: &[Object], index: &Object) -> Result<usize, Object> {
+    let len = elements.len();
+    let position = match index {
+      Object::Int(index) if *index < 0 => usize::try_from(index.unsigned_abs())
+        .ok()
+        .and_then(|offset| len.checked_sub(offset)),
+      Object::Int(index) => usize::try_from(*index)
+        .ok()
+        .filter(|&position| position < len),
+      #[cfg(feature = "bigint")]
+      Object::BigInt(_) => None,
+      index => {
+        return Err(Self::error(format!(
+          "Index must be an integer, got {}",
+          index
+        )))
+      }
+    };
+    position.ok_or_else(|| {
+      Self::error(format!(
+        "Index out of bounds: the length is {} but the index is {}",
+        len, index
+      ))
+    })
+  }
+
+  /// Assign to a variable, or to an element of one. The assignment evaluates
+  /// to the assigned value.
+  fn eval_assign_expr(
+    &mut self,
+    target: Spanned<ast::Expr>,
+    operator: Option<ast::Infix>,
+    value: Spanned<ast::Expr>,
+  ) -> Option<Object> {
+    let (ast::Ident(name), indices) = match self.eval_assign_target(target)? {
+      Ok(target) => target,
+      Err(error) => return Some(error),
+    };
+    let value = self.eval_expr(value)?;
+    if Self::is_unwinding(&value) {
+      return Some(value);
+    }
+
+    // the `Checker` reports this before evaluation already
+    if self.environment.borrow().is_const(&name) {
+      return Some(Self::error(format!(
+        "Couldn't assign to a constant: {}",
+        name
+      )));
+    }
+    let assigned = self.environment.borrow_mut().with_binding(&name, |root| {
+      let element = Self::element_mut(root, &indices, operator.is_none())?;
+      let value = match operator {
+        Some(infix) => self.eval_binary(element.clone(), infix, value),
+        None => value,
+      };
+      if Self::is_unwinding(&value) {
+        return Err(value);
+      }
+      *element = value.clone();
+      Ok(value)
+    });
+
+    Some(match assigned {
+      Some(Ok(value)) => value,
+      Some(Err(error)) => error,
+      None => Self::error(format!(
+        "Couldn't assign to an undeclared identifier: {}",
+        name
+      )),
+    })
+  }
+
+  /// The variable `target` is rooted at, along with the evaluated indices
+  /// leading from it to the assigned element, outermost first.
+  fn eval_assign_target(
+    &mut self,
+    target: Spanned<ast::Expr>,
+  ) -> Option<Result<(ast::Ident, Vec<Object>), Object>> {
+    match target.node {
+      ast::Expr::Ident(ident) => Some(Ok((ident, vec![]))),
+      ast::Expr::Index { left, index } => {
+        let (ident, mut indices) = match self.eval_assign_target(*left)? {
+          Ok(target) => target,
+          Err(error) => return Some(Err(error)),
+        };
+        let index = self.eval_expr(*index)?;
+        if Self::is_unwinding(&index) {
+          return Some(Err(index));
+        }
+        indices.push(index);
+        Some(Ok((ident, indices)))
+      }
+      // the parser only accepts the targets above
+      _ => Some(Err(Self::error(
+        "Couldn't assign to a malformed target".to_string(),
+      ))),
+    }
+  }
+
+  /// The element `indices` lead to in `container`, to assign to it in place.
+  /// Elements of arrays must exist already, unlike the last key of a hash,
+  /// which is inserted if `insert` is set.
+  fn element_mut<'container>(
+    container: &'container mut Object,
+    indices: &[Object],
+    insert: bool,
+  ) -> Result<&'container mut Object, Object> {
+    let mut element = container;
+    for (depth, index) in indices.iter().enumerate() {
+      element = match element {
+        Object::Array(elements) => {
+          let position = Self::array_position(elements, index)?;
+          &mut elements[position]
+        }
+        Object::Hash(pairs) => match pairs.entry(Self::hash_key(index)?) {
+          Entry::Occupied(entry) => entry.into_mut(),
+          Entry::Vacant(entry) if insert && depth + 1 == indices.len() => {
+            entry.insert(Object::None)
+          }
+          Entry::Vacant(_) => return Err(Self::error(format!("Key not found: {}", index))),
+        },
+        container => return Err(Self::error(format!("Couldn't index into {}", container))),
+      };
+    }
+    Ok(element)
+  }
+
+  fn apply_function(&mut self, function: Object, args: Vec<Object>) -> Option<Object> {
+    let Function { params, body, env } = match function {
+      Object::Function(function) => function,
+      _ => return Some(Self::error(format!("Not a function: {}", function))),
+    };
+
+    if params.len() != args.len() {
+      return Some(Self::error(format!(
+        "Wrong number of arguments: expected {}, got {}",
+        params.len(),
+        args.len()
+      )));
+    }
+
+    let mut scope = Environment::enclose(env);
+    for (ast::Ident(name), arg) in params.into_iter().zip(args) {
+      scope.set(&name, arg);
+    }
+
+    match self.eval_in_scope(scope.into_shared(), body)? {
+      // `return` only unwinds the function being called
+      Object::ReturnValue(val) => Some(*val),
+      signal @ (Object::Break | Object::Continue) => {
+        Some(Self::error(Self::loop_control_error(&signal)))
+      }
+      // the function may be defined in an earlier input, which its spans point
+      // into, so an error in its body is located at the call instead
+      Object::Error(error) => Some(Object::Error(error.unlocated())),
+      val => Some(val),
     }
   }
 }
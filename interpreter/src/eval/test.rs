@@ -5,7 +5,9 @@ macro_rules! eval {
     let mut parser = Parser::new(Lexer::new($input));
     let parser = parser.parse();
     let mut evaluator = super::Evaluator::new();
-    evaluator.eval(parser.get_stmts())
+    evaluator
+      .eval(parser.get_stmts())
+      .map_err(|error| error.to_string())
   }};
 }
 
@@ -21,6 +23,8 @@ fn eval_integer_expr() {
     ("1 + 2 + 3 + 4", 10),
     ("1 + 2 - 3 + 4", 4),
     ("1 + (2 * 3) / 4", 2),
+    ("0xff + 0o7 + 0b1", 263),
+    ("1_000 * 2", 2000),
   ];
 
   for (input, expected) in cases {
@@ -28,6 +32,255 @@ fn eval_integer_expr() {
   }
 }
 
+#[test]
+fn eval_power_expr() {
+  let cases = vec![
+    ("2 ** 10", 1024),
+    ("2 ** 3 ** 2", 512),
+    ("(2 ** 3) ** 2", 64),
+    ("-2 ** 2", -4),
+    ("(-2) ** 3", -8),
+    ("2 * 3 ** 2", 18),
+    ("7 ** 0", 1),
+    ("1 ** 9223372036854775807", 1),
+    ("(-1) ** 9223372036854775807", -1),
+    ("3 ** 39", 4052555153018976267),
+  ];
+
+  for (input, expected) in cases {
+    assert_eq!(eval!(input), Ok(Object::Int(expected)), "{}", input)
+  }
+
+  let cases = vec![
+    ("2 ** 0.5", 2f64.sqrt()),
+    ("4.0 ** -1", 0.25),
+    ("0.5 ** 2", 0.25),
+  ];
+
+  for (input, expected) in cases {
+    assert_eq!(eval!(input), Ok(Object::Float(expected)), "{}", input)
+  }
+}
+
+#[test]
+fn eval_bitwise_expr() {
+  let cases = vec![
+    ("7 % 3", 1),
+    ("-7 % 3", -1),
+    ("7 % -3", 1),
+    ("0b1100 & 0b1010", 0b1000),
+    ("0b1100 | 0b1010", 0b1110),
+    ("0b1100 ^ 0b1010", 0b0110),
+    ("~0", -1),
+    ("~5", -6),
+    ("1 << 10", 1024),
+    ("1 << 63", i64::MIN),
+    ("-16 >> 2", -4),
+    ("-1 >> 63", -1),
+    ("1 | 2 ^ 3 & 4", 3),
+    ("1 << 2 + 1", 8),
+  ];
+
+  for (input, expected) in cases {
+    assert_eq!(eval!(input), Ok(Object::Int(expected)), "{}", input)
+  }
+
+  assert_eq!(eval!("7.5 % 2"), Ok(Object::Float(1.5)));
+
+  let cases = vec![
+    ("1 % 0", "Division by zero: 1 % 0"),
+    ("1 << -1", "Negative shift count: 1 << -1"),
+    ("1 >> 64", "Shift count too large: 1 >> 64"),
+    ("1.5 & 1", "Couldn't perform operation: 1.5 & 1"),
+    ("1 << 1.0", "Couldn't perform operation: 1 << 1.0"),
+    ("~true", "Illegal syntax: ~true"),
+    // `&` binds looser than `==`, like in C
+    ("6 & 3 == 2", "Couldn't perform operation: 6 & false"),
+  ];
+
+  for (input, expected) in cases {
+    assert_eq!(eval!(input), Err(expected.to_string()), "{}", input)
+  }
+}
+
+#[test]
+fn eval_float_expr() {
+  let cases = vec![
+    ("1.5", 1.5),
+    ("-2.5", -2.5),
+    ("+2.5", 2.5),
+    ("1.5 + 1.5", 3.0),
+    ("1 + 0.5", 1.5),
+    ("0.5 + 1", 1.5),
+    ("3 - 0.5", 2.5),
+    ("2 * 1.25", 2.5),
+    ("1 / 4.0", 0.25),
+    ("1e3 / 10", 100.0),
+    ("1.0 / 0", f64::INFINITY),
+    ("-1 / 0.0", f64::NEG_INFINITY),
+  ];
+
+  for (input, expected) in cases {
+    assert_eq!(eval!(input), Ok(Object::Float(expected)), "{}", input)
+  }
+
+  match eval!("0.0 / 0") {
+    Ok(Object::Float(val)) => assert!(val.is_nan()),
+    result => panic!("expected NaN, got {:?}", result),
+  }
+  // int division stays integer division
+  assert_eq!(eval!("7 / 2"), Ok(Object::Int(3)));
+}
+
+#[test]
+fn float_comparisons() {
+  let cases = vec![
+    ("1 == 1.0", true),
+    ("1.0 != 1", false),
+    ("0.1 + 0.2 == 0.3", false),
+    ("1 < 1.5", true),
+    ("2.5 >= 2", true),
+    ("[1, 2.0] == [1.0, 2]", true),
+    ("{1: 1}[1] == 1.0", true),
+    ("let inf = 1 / 0.0; inf > 1e308", true),
+    ("let inf = 1 / 0.0; -inf < inf", true),
+    ("let inf = 1 / 0.0; inf == inf", true),
+    // NaN is unordered and isn't equal to anything, itself included
+    ("let nan = 0.0 / 0; nan == nan", false),
+    ("let nan = 0.0 / 0; nan != nan", true),
+    ("let nan = 0.0 / 0; nan < 1", false),
+    ("let nan = 0.0 / 0; nan >= 1", false),
+    ("let nan = 0.0 / 0; 1 <= nan", false),
+  ];
+
+  for (input, expected) in cases {
+    assert_eq!(eval!(input), Ok(Object::Bool(expected)), "{}", input)
+  }
+}
+
+#[test]
+fn display_float() {
+  let cases = vec![
+    ("1.0", "1.0"),
+    ("2.5e-3", "0.0025"),
+    ("1e100", "1e100"),
+    ("1 / 0.0", "inf"),
+    ("0.0 / 0", "NaN"),
+  ];
+
+  for (input, expected) in cases {
+    assert_eq!(eval!(input).unwrap().to_string(), expected)
+  }
+}
+
+#[test]
+fn catch_arithmetic_error() {
+  let cases = vec![
+    ("1 / 0", "Division by zero: 1 / 0"),
+    ("let zero = 1 - 1; 5 / zero", "Division by zero: 5 / 0"),
+    ("2 ** -1", "Negative exponent: 2 ** -1"),
+  ];
+
+  for (input, expected) in cases {
+    assert_eq!(eval!(input), Err(expected.to_string()), "{}", input)
+  }
+}
+
+#[test]
+#[cfg(not(feature = "bigint"))]
+fn catch_overflow_error() {
+  let cases = vec![
+    (
+      "9223372036854775807 + 1",
+      "Integer overflow: 9223372036854775807 + 1",
+    ),
+    (
+      "-9223372036854775807 - 2",
+      "Integer overflow: -9223372036854775807 - 2",
+    ),
+    (
+      "0x7fff_ffff_ffff_ffff * 2",
+      "Integer overflow: 9223372036854775807 * 2",
+    ),
+    (
+      "let min = -9223372036854775807 - 1; min / -1",
+      "Integer overflow: -9223372036854775808 / -1",
+    ),
+    (
+      "let min = -9223372036854775807 - 1; -min",
+      "Integer overflow: -(-9223372036854775808)",
+    ),
+    ("2 ** 63", "Integer overflow: 2 ** 63"),
+    ("(-3) ** 41", "Integer overflow: -3 ** 41"),
+    ("21!", "Integer overflow: 21!"),
+    (
+      "let min = -9223372036854775807 - 1; min % -1",
+      "Integer overflow: -9223372036854775808 % -1",
+    ),
+  ];
+
+  for (input, expected) in cases {
+    assert_eq!(eval!(input), Err(expected.to_string()), "{}", input)
+  }
+}
+
+#[test]
+fn eval_arithmetic_modes() {
+  use super::ArithmeticMode;
+  use pratt_parsing::lexer::Lexer;
+  use pratt_parsing::parser::Parser;
+
+  let min = "let min = -9223372036854775807 - 1;";
+  let cases = vec![
+    ("9223372036854775807 + 1", i64::MIN, i64::MAX),
+    ("-9223372036854775807 - 2", i64::MAX, i64::MIN),
+    ("4611686018427387904 * 2", i64::MIN, i64::MAX),
+    ("4611686018427387904 * -4", 0, i64::MIN),
+    ("min / -1", i64::MIN, i64::MAX),
+    ("min % -1", 0, 0),
+    ("-min", i64::MIN, i64::MAX),
+    ("7 / 2", 3, 3),
+    ("2 ** 64", 0, i64::MAX),
+    ("(-3) ** 41", (-3i64).wrapping_pow(41), i64::MIN),
+    ("21!", 21i64.wrapping_mul(2432902008176640000), i64::MAX),
+    ("100!", 0, i64::MAX),
+  ];
+
+  for (input, wrapping, saturating) in cases {
+    let input = format!("{} {}", min, input);
+    for (mode, expected) in [
+      (ArithmeticMode::Wrapping, wrapping),
+      (ArithmeticMode::Saturating, saturating),
+    ] {
+      let mut parser = Parser::new(Lexer::new(&input));
+      let parser = parser.parse();
+      let mut evaluator = super::Evaluator::new().with_arithmetic_mode(mode);
+
+      assert_eq!(
+        evaluator.eval(parser.get_stmts()),
+        Ok(Object::Int(expected)),
+        "{} ({:?})",
+        input,
+        mode
+      );
+    }
+  }
+
+  // dividing by zero is an error whatever the mode
+  for mode in [ArithmeticMode::Wrapping, ArithmeticMode::Saturating] {
+    let mut parser = Parser::new(Lexer::new("1 / 0"));
+    let parser = parser.parse();
+    let mut evaluator = super::Evaluator::new().with_arithmetic_mode(mode);
+
+    assert_eq!(
+      evaluator
+        .eval(parser.get_stmts())
+        .map_err(|error| error.to_string()),
+      Err("Division by zero: 1 / 0".to_string())
+    );
+  }
+}
+
 #[test]
 fn eval_bool_expr() {
   let cases = vec![
@@ -46,6 +299,18 @@ fn eval_bool_expr() {
     ("(1 < 2) == false", false),
     ("(1 > 2) == true", false),
     ("(1 > 2) == false", true),
+    ("1 <= 2", true),
+    ("2 <= 2", true),
+    ("3 <= 2", false),
+    ("1 >= 2", false),
+    ("2 >= 2", true),
+    ("2 ≥ 1", true),
+    ("2 ≤ 1", false),
+    ("1 ≠ 2", true),
+    ("2 × 3 == 12 ÷ 2", true),
+    ("1 + 1 <= 2 == true", true),
+    (r#""abc" <= "abd""#, true),
+    (r#""" >= "a""#, false),
   ];
 
   for (input, expected) in cases {
@@ -65,6 +330,7 @@ fn eval_if_else_expr() {
     ("if (1 < 2) { 3 } else { 4 }", Ok(Object::Int(3))),
     ("if (1 * 2 + 3 / 4 - 5) { 6 }", Ok(Object::Int(6))),
     ("if (1 * (2 + 3) / 4 - 5) { 6;\n 7 }", Ok(Object::Int(7))),
+    ("if (false) { 1 }; 2", Ok(Object::Int(2))),
   ];
 
   for (input, expected) in cases {
@@ -72,6 +338,169 @@ fn eval_if_else_expr() {
   }
 }
 
+#[test]
+fn catch_if_condition_error() {
+  // these used to evaluate to `None`, like an `if` whose condition is false
+  let cases = vec![
+    ("if (\"s\") { 1 }", "Couldn't use \"s\" as a condition"),
+    ("if ([]) { 1 } else { 2 }", "Couldn't use [] as a condition"),
+    ("if ({}) { 1 }", "Couldn't use {} as a condition"),
+    (
+      "if (if (false) { 1 }) { 2 }",
+      "Couldn't use None as a condition",
+    ),
+    (
+      "if (fn() { 1 }) { 2 }",
+      "Couldn't use fn() { ... } as a condition",
+    ),
+  ];
+
+  for (input, expected) in cases {
+    assert_eq!(eval!(input), Err(expected.to_string()), "{}", input)
+  }
+}
+
+#[test]
+fn eval_string_expr() {
+  let cases = vec![
+    (r#""hello""#, Object::Str("hello".to_string())),
+    (
+      r#""hello" + ", " + "world""#,
+      Object::Str("hello, world".to_string()),
+    ),
+    (
+      r#"let s = "a\tb"; s + s"#,
+      Object::Str("a\tba\tb".to_string()),
+    ),
+    (r#""\u{48}i""#, Object::Str("Hi".to_string())),
+    (r#""a" == "a""#, Object::Bool(true)),
+    (r#""a" != "b""#, Object::Bool(true)),
+    (r#""a" == 1"#, Object::Bool(false)),
+    (r#""a" < "b""#, Object::Bool(true)),
+    (r#""abc" < "abd""#, Object::Bool(true)),
+    (r#""b" > "abc""#, Object::Bool(true)),
+    (r#""" > "a""#, Object::Bool(false)),
+  ];
+
+  for (input, expected) in cases {
+    assert_eq!(eval!(input), Ok(expected))
+  }
+}
+
+#[test]
+fn eval_array_expr() {
+  let cases = vec![
+    ("[]", Object::Array(vec![])),
+    (
+      "[1, 2 * 2, 3 + 3]",
+      Object::Array(vec![Object::Int(1), Object::Int(4), Object::Int(6)]),
+    ),
+    ("[1, 2, 3][0]", Object::Int(1)),
+    ("[1, 2, 3][1 + 1]", Object::Int(3)),
+    ("let i = 0; [1][i]", Object::Int(1)),
+    (
+      "let arr = [1, 2, 3]; arr[0] + arr[1] + arr[2]",
+      Object::Int(6),
+    ),
+    (
+      "let arr = [1, 2, 3]; let i = arr[0]; arr[i]",
+      Object::Int(2),
+    ),
+    ("[1, 2, 3][-1]", Object::Int(3)),
+    ("[1, 2, 3][-3]", Object::Int(1)),
+    ("[[1, 2], [3]][0][1]", Object::Int(2)),
+    ("let f = fn() { [1, 2] }; f()[1]", Object::Int(2)),
+    ("[1, 2] == [1, 2]", Object::Bool(true)),
+    ("[1, 2] == [2, 1]", Object::Bool(false)),
+  ];
+
+  for (input, expected) in cases {
+    assert_eq!(eval!(input), Ok(expected))
+  }
+}
+
+#[test]
+fn catch_index_error() {
+  let cases = vec![
+    (
+      "[1, 2, 3][3]",
+      "Index out of bounds: the length is 3 but the index is 3",
+    ),
+    (
+      "[1, 2, 3][-4]",
+      "Index out of bounds: the length is 3 but the index is -4",
+    ),
+    (
+      "[][0]",
+      "Index out of bounds: the length is 0 but the index is 0",
+    ),
+    ("[1][true]", "Index must be an integer, got true"),
+    ("1[0]", "Couldn't index into 1"),
+    ("[1 + true][0]", "Couldn't perform operation: 1 + true"),
+    ("[1][x]", "Identifier not found: x"),
+  ];
+
+  for (input, expected) in cases {
+    assert_eq!(eval!(input), Err(expected.to_string()))
+  }
+}
+
+#[test]
+fn eval_hash_expr() {
+  use crate::object::HashKey;
+  use std::collections::BTreeMap;
+
+  let cases = vec![
+    ("{}", Object::Hash(BTreeMap::new())),
+    (
+      r#"let two = "two"; {"one": 10 - 9, two: 1 + 1, 3: 3, true: 4, 3: 5}"#,
+      Object::Hash(BTreeMap::from([
+        (HashKey::Str("one".to_string()), Object::Int(1)),
+        (HashKey::Str("two".to_string()), Object::Int(2)),
+        (HashKey::Int(3), Object::Int(5)),
+        (HashKey::Bool(true), Object::Int(4)),
+      ])),
+    ),
+    (r#"{"a": 1}["a"]"#, Object::Int(1)),
+    (r#"let key = "a"; {"a": 1}[key]"#, Object::Int(1)),
+    ("{1: true}[1]", Object::Bool(true)),
+    ("{false: [1, 2]}[1 > 2][1]", Object::Int(2)),
+    (
+      r#"let h = {"f": fn(x) { x * 2 }}; h["f"](2)"#,
+      Object::Int(4),
+    ),
+  ];
+
+  for (input, expected) in cases {
+    assert_eq!(eval!(input), Ok(expected))
+  }
+}
+
+#[test]
+fn display_hash() {
+  let object = eval!(r#"{"b": [1, "c"], 2: {}, true: 1, "a": 2}"#).unwrap();
+
+  assert_eq!(
+    object.to_string(),
+    r#"{2: {}, true: 1, "a": 2, "b": [1, "c"]}"#
+  );
+}
+
+#[test]
+fn catch_hash_error() {
+  let cases = vec![
+    ("{fn(x) { x }: 1}", "Unhashable hash key: fn(x) { ... }"),
+    ("{[1]: 1}", "Unhashable hash key: [1]"),
+    ("{1: 1}[[1]]", "Unhashable hash key: [1]"),
+    (r#"{1: 1}["a"]"#, r#"Key not found: "a""#),
+    ("{1: x}", "Identifier not found: x"),
+  ];
+
+  for (input, expected) in cases {
+    assert_eq!(eval!(input), Err(expected.to_string()))
+  }
+}
+
 #[test]
 fn eval_bang_operator() {
   let cases = vec![
@@ -91,6 +520,39 @@ fn eval_bang_operator() {
   }
 }
 
+#[test]
+fn eval_logical_expr() {
+  let cases = vec![
+    ("true && true", true),
+    ("true && false", false),
+    ("false || true", true),
+    ("false || false", false),
+    ("1 && 2.5", true),
+    ("0 || 0.0", false),
+    ("1 < 2 && 2 < 3", true),
+    ("false && true || true", true),
+    ("true || false && false", true),
+    // the right operand isn't evaluated, so the unknown identifier is fine
+    ("false && missing", false),
+    ("true || missing", true),
+    ("let a = [1]; 1 < 0 && a[5] == 1", false),
+  ];
+
+  for (input, expected) in cases {
+    assert_eq!(eval!(input), Ok(Object::Bool(expected)), "{}", input)
+  }
+
+  let cases = vec![
+    ("true && missing", "Identifier not found: missing"),
+    (r#""yes" && true"#, r#"Couldn't use "yes" as a condition"#),
+    ("false || [1]", "Couldn't use [1] as a condition"),
+  ];
+
+  for (input, expected) in cases {
+    assert_eq!(eval!(input), Err(expected.to_string()), "{}", input)
+  }
+}
+
 #[test]
 fn eval_prefix_minus_operator() {
   let cases = vec![("-1", -1), ("-10", -10)];
@@ -146,6 +608,361 @@ fn eval_return_stmt() {
   }
 }
 
+#[test]
+fn eval_while_expr() {
+  let cases = vec![
+    ("while (false) { 1 }", Object::None),
+    ("while (true) { break; }", Object::None),
+    ("while (1) { if (true) { break } 2 }", Object::None),
+    (
+      "let f = fn() { while (true) { return 3; } }; f()",
+      Object::Int(3),
+    ),
+    (
+      "let f = fn(n) { while (n) { if (n > 1) { continue } return n; } }; f(1)",
+      Object::Int(1),
+    ),
+    (
+      "let f = fn() { while (true) { while (true) { break; } return 4; } }; f()",
+      Object::Int(4),
+    ),
+  ];
+
+  for (input, expected) in cases {
+    assert_eq!(eval!(input), Ok(expected), "{}", input)
+  }
+}
+
+#[test]
+fn eval_for_expr() {
+  let cases = vec![
+    (
+      "for (x in [1, 2, 3]) { if (x > 1) { return x } }",
+      Object::Int(2),
+    ),
+    ("for (x in 1..3) { if (x == 3) { return x } }", Object::None),
+    (
+      "for (x in 1..=3) { if (x == 3) { return x } }",
+      Object::Int(3),
+    ),
+    ("for (x in 3..1) { return x }", Object::None),
+    (
+      "for (i, x in [4, 5, 6]) { if (x == 5) { return i } }",
+      Object::Int(1),
+    ),
+    (
+      "for (i, x in 2..5) { if (i == 2) { return x } }",
+      Object::Int(4),
+    ),
+    (
+      "for (c in \"héllo\") { if (c != \"h\") { return c } }",
+      Object::Str("é".to_string()),
+    ),
+    (
+      "for (i, c in \"abc\") { if (c == \"c\") { return i } }",
+      Object::Int(2),
+    ),
+    (
+      "for (k in {\"b\": 2, \"a\": 1}) { return k }",
+      Object::Str("a".to_string()),
+    ),
+    (
+      "for (k, v in {\"b\": 2, \"a\": 1}) { if (k == \"b\") { return v } }",
+      Object::Int(2),
+    ),
+    (
+      "for (x in 1..10) { if (x < 5) { continue } return x }",
+      Object::Int(5),
+    ),
+    ("for (x in 1..10) { break; }", Object::None),
+    (
+      "let f = fn(xs) { for (x in xs) { if (x % 2 == 0) { return x } } }; f([1, 3, 8, 10])",
+      Object::Int(8),
+    ),
+  ];
+
+  for (input, expected) in cases {
+    assert_eq!(eval!(input), Ok(expected), "{}", input)
+  }
+
+  let cases = vec![("1..3", "1..3"), ("-2..=2", "-2..=2")];
+
+  for (input, expected) in cases {
+    assert_eq!(
+      eval!(input).map(|r| r.to_string()),
+      Ok(expected.to_string())
+    )
+  }
+}
+
+#[test]
+fn catch_loop_error() {
+  let cases = vec![
+    ("break", "Couldn't break outside of a loop"),
+    (
+      "if (true) { continue; }",
+      "Couldn't continue outside of a loop",
+    ),
+    (
+      "let f = fn() { break; }; while (true) { f() }",
+      "Couldn't break outside of a loop",
+    ),
+    ("while (\"a\") { 1 }", "Couldn't use \"a\" as a condition"),
+    (
+      "while (true) { 1 + true }",
+      "Couldn't perform operation: 1 + true",
+    ),
+    ("for (x in 5) { x }", "Couldn't iterate over 5"),
+    ("for (x in [1]) { x }; x", "Identifier not found: x"),
+    ("1..true", "Couldn't perform operation: 1 .. true"),
+    ("1.5..=2", "Couldn't perform operation: 1.5 ..= 2"),
+  ];
+
+  for (input, expected) in cases {
+    assert_eq!(eval!(input), Err(expected.to_string()), "{}", input)
+  }
+}
+
+#[test]
+fn eval_assign_expr() {
+  let cases = vec![
+    ("let a = 1; a = 2; a", Object::Int(2)),
+    ("let a = 1; a = 2", Object::Int(2)),
+    ("let a = 1; let b = 2; a = b = 3; a + b", Object::Int(6)),
+    ("let a = 1; if (true) { a = 2; }; a", Object::Int(2)),
+    (
+      "let a = 1; if (true) { let a = 5; a = 2; }; a",
+      Object::Int(1),
+    ),
+    (
+      "let a = 7; a += 3; a -= 1; a *= 2; a /= 3; a",
+      Object::Int(6),
+    ),
+    ("let a = 7; a %= 4; a **= 3; a", Object::Int(27)),
+    (
+      "let a = 6; a &= 3; a |= 8; a ^= 1; a <<= 2; a >>= 1",
+      Object::Int(22),
+    ),
+    (
+      "let s = \"a\"; s += \"b\"; s",
+      Object::Str("ab".to_string()),
+    ),
+    (
+      "let sum = 0; for (x in 1..=10) { sum += x }; sum",
+      Object::Int(55),
+    ),
+    (
+      "let n = 0; let i = 0; while (i < 10) { i += 1; if (i % 2 == 0) { continue } n += i }; n",
+      Object::Int(25),
+    ),
+    (
+      "let counter = fn() { let n = 0; fn() { n += 1 } }; let next = counter(); next(); next()",
+      Object::Int(2),
+    ),
+  ];
+
+  for (input, expected) in cases {
+    assert_eq!(eval!(input), Ok(expected), "{}", input)
+  }
+
+  let cases = vec![
+    ("let a = [1, 2, 3]; a[0] = 4; a", "[4, 2, 3]"),
+    ("let a = [1, 2, 3]; a[-1] += 10; a", "[1, 2, 13]"),
+    ("let a = [[1], [2]]; a[1][0] = 3; a", "[[1], [3]]"),
+    (
+      "let h = {\"a\": 1}; h[\"b\"] = 2; h[\"a\"] -= 1; h",
+      "{\"a\": 0, \"b\": 2}",
+    ),
+    ("let h = {1: [0]}; h[1][0] = true; h", "{1: [true]}"),
+    ("let a = [0]; let b = a; b[0] = 1; [a, b]", "[[0], [1]]"),
+  ];
+
+  for (input, expected) in cases {
+    assert_eq!(
+      eval!(input).map(|r| r.to_string()),
+      Ok(expected.to_string()),
+      "{}",
+      input
+    )
+  }
+}
+
+#[test]
+fn eval_const_stmt() {
+  let cases = vec![
+    ("const a = 1; a", 1),
+    ("const a = 1; let b = a; b += 1; b", 2),
+    ("const a = 1; if (true) { let a = 2; a = 3; a }", 3),
+  ];
+
+  for (input, expected) in cases {
+    assert_eq!(eval!(input), Ok(Object::Int(expected)), "{}", input)
+  }
+}
+
+#[test]
+fn eval_match_expr() {
+  let cases = vec![
+    ("match (2) { 1 => 10, 2 => 20, _ => 0 }", Object::Int(20)),
+    ("match (3) { 1 => 10, _ => 0 }", Object::Int(0)),
+    (
+      "match (2.0) { 2 => \"int\", _ => \"other\" }",
+      Object::Str("int".to_string()),
+    ),
+    ("match (-1) { -1 => true, _ => false }", Object::Bool(true)),
+    (
+      "match (\"a\") { \"b\" => 1, s => s }",
+      Object::Str("a".to_string()),
+    ),
+    ("match (5) { x if x > 3 => x * 2, x => x }", Object::Int(10)),
+    ("match (1) { x if x > 3 => x * 2, x => x }", Object::Int(1)),
+    (
+      "match ([1, 2]) { [a] => a, [a, b] => a + b }",
+      Object::Int(3),
+    ),
+    (
+      "match ([1, 2, 3]) { [a, b] => [], [a, ..rest] => rest }",
+      Object::Array(vec![Object::Int(2), Object::Int(3)]),
+    ),
+    ("match ([]) { [_, ..] => 1, [..] => 2 }", Object::Int(2)),
+    ("match ([[1, 2]]) { [[1, x]] => x, _ => 0 }", Object::Int(2)),
+    (
+      "match ({\"a\": 1, \"b\": 2}) { {\"a\": 2} => 0, {\"b\": b} => b }",
+      Object::Int(2),
+    ),
+    ("match ({}) { {\"a\": a} => a, _ => 0 }", Object::Int(0)),
+    ("match (1) { [..] => 1, {} => 2, _ => 3 }", Object::Int(3)),
+    ("let x = 1; match (2) { x => x }; x", Object::Int(1)),
+    (
+      "match (2) { x => { let y = x * 2; y + 1 } }",
+      Object::Int(5),
+    ),
+    (
+      "let y = 1; match (2) { x => { let y = x; y } }; y",
+      Object::Int(1),
+    ),
+    (
+      "match (true) { false => 0, true => ({\"a\": 1})[\"a\"] }",
+      Object::Int(1),
+    ),
+    (
+      "let f = fn(x) { match (x) { 0 => if (true) { return 1 }, _ => 2 }; 3 }; f(0)",
+      Object::Int(1),
+    ),
+  ];
+
+  for (input, expected) in cases {
+    assert_eq!(eval!(input), Ok(expected), "{}", input)
+  }
+}
+
+#[test]
+fn catch_match_error() {
+  let cases = vec![
+    ("match (3) { 1 => 1, 2 => 2 }", "Couldn't match 3"),
+    ("match ([1]) { [] => 0 }", "Couldn't match [1]"),
+    (
+      "match (1) { x if \"a\" => x }",
+      "Couldn't use \"a\" as a condition",
+    ),
+    ("match (1) { x if y => x }", "Identifier not found: y"),
+  ];
+
+  for (input, expected) in cases {
+    assert_eq!(eval!(input), Err(expected.to_string()), "{}", input)
+  }
+}
+
+#[test]
+fn catch_assign_error() {
+  let cases = vec![
+    ("const a = 1; a = 2", "Couldn't assign to a constant: a"),
+    (
+      "const a = [1]; let f = fn() { a[0] += 1 }; f()",
+      "Couldn't assign to a constant: a",
+    ),
+    ("a = 1", "Couldn't assign to an undeclared identifier: a"),
+    ("const a = 1; let a = 2", "Couldn't redeclare a constant: a"),
+    (
+      "const a = 1; const a = 2",
+      "Couldn't redeclare a constant: a",
+    ),
+    (
+      "if (true) { let a = 1; }; a += 1",
+      "Couldn't assign to an undeclared identifier: a",
+    ),
+    (
+      "let a = true; a += 1",
+      "Couldn't perform operation: true + 1",
+    ),
+    (
+      "let a = [1]; a[1] = 2",
+      "Index out of bounds: the length is 1 but the index is 1",
+    ),
+    (
+      "let a = [1]; a[\"x\"] = 2",
+      "Index must be an integer, got \"x\"",
+    ),
+    ("let h = {}; h[[]] = 1", "Unhashable hash key: []"),
+    ("let h = {}; h[\"a\"][0] = 1", "Key not found: \"a\""),
+    ("let h = {}; h[\"a\"] += 1", "Key not found: \"a\""),
+    ("let s = \"ab\"; s[0] = \"c\"", "Couldn't index into \"ab\""),
+    ("let a = 1; a = b", "Identifier not found: b"),
+  ];
+
+  for (input, expected) in cases {
+    assert_eq!(eval!(input), Err(expected.to_string()), "{}", input)
+  }
+}
+
+#[test]
+fn eval_postfix_expr() {
+  let cases = vec![
+    ("0!", 1),
+    ("5!", 120),
+    ("3!!", 720),
+    ("-3!", -6),
+    ("2 ** 3!", 64),
+    ("20!", 2432902008176640000),
+  ];
+
+  for (input, expected) in cases {
+    assert_eq!(eval!(input), Ok(Object::Int(expected)), "{}", input)
+  }
+
+  let cases = vec![
+    ("(-1)!", "Factorial of a negative number: -1!"),
+    ("1.5!", "Illegal syntax: 1.5!"),
+    (r#""a"!"#, r#"Illegal syntax: "a"!"#),
+  ];
+
+  for (input, expected) in cases {
+    assert_eq!(eval!(input), Err(expected.to_string()), "{}", input)
+  }
+
+  // `?` returns early from the function if its operand has no value
+  let lookup = "
+    let lookup = fn(found) { if (found) { 1 } };
+    let add_one = fn(found) { let value = lookup(found)?; value + 1 };";
+  let cases = vec![
+    ("add_one(true)", "2"),
+    ("add_one(false)", "None"),
+    ("[add_one(false), add_one(true)]", "[None, 2]"),
+    ("lookup(true)? + 1", "2"),
+  ];
+
+  for (input, expected) in cases {
+    let input = format!("{} {}", lookup, input);
+    let input = input.as_str();
+    assert_eq!(
+      eval!(input).map(|result| result.to_string()),
+      Ok(expected.to_string()),
+      "{}",
+      input
+    )
+  }
+}
+
 #[test]
 fn catch_internal_error() {
   let cases = vec![
@@ -153,6 +970,10 @@ fn catch_internal_error() {
     ("1 + true; 2", "Couldn't perform operation: 1 + true"),
     ("+true", "Illegal syntax: +true"),
     ("true * false", "Couldn't perform operation: true * false"),
+    // operators are spelled in ASCII, however they're written
+    ("\"a\" <= 1", "Couldn't perform operation: \"a\" <= 1"),
+    ("[] ≥ 1", "Couldn't perform operation: [] >= 1"),
+    ("true ÷ false", "Couldn't perform operation: true / false"),
     (
       "
     if (1 < 2) {
@@ -171,9 +992,240 @@ fn catch_internal_error() {
       "Couldn't perform operation: true / false",
     ),
     ("foo", "Identifier not found: foo"),
+    (r#""a" + 1"#, r#"Couldn't perform operation: "a" + 1"#),
+    (r#""a" - "b""#, r#"Couldn't perform operation: "a" - "b""#),
+    (r#""a" < 1"#, r#"Couldn't perform operation: "a" < 1"#),
+    ("1.5 + true", "Couldn't perform operation: 1.5 + true"),
+    ("{1.5: 1}", "Unhashable hash key: 1.5"),
+    ("let = 1; 2", "Couldn't evaluate a malformed statement"),
   ];
 
   for (input, expected) in cases {
     assert_eq!(eval!(input), Err(expected.to_string()))
   }
 }
+
+#[test]
+fn eval_with_comments() {
+  let cases = vec![
+    ("// one\n1", 1),
+    ("1 // one", 1),
+    ("/* a /* nested */ comment */ 2", 2),
+    ("let x = 1; // x\nlet y = /* y */ 2;\n/// sum\nx + y", 3),
+    ("4 / /* divided by */ 2", 2),
+  ];
+
+  for (input, expected) in cases {
+    assert_eq!(eval!(input), Ok(Object::Int(expected)))
+  }
+}
+
+#[test]
+fn eval_function_call() {
+  let cases = vec![
+    ("let identity = fn(x) { x; }; identity(5);", 5),
+    ("let identity = fn(x) { return x; }; identity(5);", 5),
+    ("let double = fn(x) { x * 2; }; double(5);", 10),
+    ("let add = fn(a, b) { a + b }; add(1, 2)", 3),
+    ("let add = fn(x, y) { x + y; }; add(5 + 5, add(5, 5));", 20),
+    ("fn(x) { x; }(5)", 5),
+    ("let f = fn() { return 1; 2 }; f() + 1", 2),
+  ];
+
+  for (input, expected) in cases {
+    assert_eq!(eval!(input), Ok(Object::Int(expected)))
+  }
+}
+
+#[test]
+fn eval_closures() {
+  let cases = vec![
+    (
+      "let new_adder = fn(x) { fn(y) { x + y } };
+      let add_two = new_adder(2);
+      add_two(3);",
+      5,
+    ),
+    (
+      "let x = 10;
+      let f = fn(x) { x };
+      f(1) + x",
+      11,
+    ),
+    (
+      "let fib = fn(n) {
+        if (n < 2) { return n; }
+        fib(n - 1) + fib(n - 2)
+      };
+      fib(10)",
+      55,
+    ),
+  ];
+
+  for (input, expected) in cases {
+    assert_eq!(eval!(input), Ok(Object::Int(expected)))
+  }
+}
+
+#[test]
+fn catch_call_error() {
+  let cases = vec![
+    (
+      "let add = fn(a, b) { a + b }; add(1)",
+      "Wrong number of arguments: expected 2, got 1",
+    ),
+    ("let a = 1; a(2)", "Not a function: 1"),
+    ("let f = fn(x) { x }; f(y)", "Identifier not found: y"),
+    ("if (true) { let y = 1; }; y", "Identifier not found: y"),
+    (
+      "let f = fn() { 1 + true }; f()",
+      "Couldn't perform operation: 1 + true",
+    ),
+  ];
+
+  for (input, expected) in cases {
+    assert_eq!(eval!(input), Err(expected.to_string()))
+  }
+}
+
+#[test]
+fn eval_nested_scopes() {
+  let cases = vec![
+    ("let x = 1; if (true) { let x = 2; x }", 2),
+    ("let x = 1; if (true) { let x = 2; }; x", 1),
+    ("let x = 1; if (true) { let y = x + 1; y }", 2),
+    ("let x = 1; let f = fn() { let x = 2; x }; f() + x", 3),
+    (
+      "let x = 1;
+      let f = fn() { x };
+      let g = fn() { let x = 2; f() };
+      g()",
+      1,
+    ),
+  ];
+
+  for (input, expected) in cases {
+    assert_eq!(eval!(input), Ok(Object::Int(expected)))
+  }
+}
+
+#[test]
+fn eval_across_inputs() {
+  use pratt_parsing::lexer::Lexer;
+  use pratt_parsing::parser::Parser;
+
+  let mut evaluator = super::Evaluator::new();
+  let cases = vec![
+    ("let x = 1;", Ok(Object::Int(1))),
+    ("let add = fn(a) { a + x }; 0", Ok(Object::Int(0))),
+    ("add(2)", Ok(Object::Int(3))),
+    ("y", Err("Identifier not found: y".to_string())),
+    ("let x = 10; add(2)", Ok(Object::Int(12))),
+  ];
+
+  for (input, expected) in cases {
+    // the evaluator keeps functions around after their input is dropped
+    let input = input.to_string();
+    let mut parser = Parser::new(Lexer::new(&input));
+    let parser = parser.parse();
+    assert_eq!(
+      evaluator
+        .eval(parser.get_stmts())
+        .map_err(|error| error.to_string()),
+      expected
+    );
+  }
+}
+
+#[test]
+#[cfg(feature = "bigint")]
+fn eval_bigint_expr() {
+  let cases = vec![
+    ("9223372036854775807 + 1", "9223372036854775808"),
+    ("-9223372036854775807 - 2", "-9223372036854775809"),
+    (
+      "99999999999999999999 * 99999999999999999999",
+      "9999999999999999999800000000000000000001",
+    ),
+    ("100000000000000000000 / 3", "33333333333333333333"),
+    ("-100000000000000000000 / 3", "-33333333333333333333"),
+    (
+      "let min = -9223372036854775807 - 1; min / -1",
+      "9223372036854775808",
+    ),
+    (
+      "let min = -9223372036854775807 - 1; -min",
+      "9223372036854775808",
+    ),
+    ("{9223372036854775808: 1}", "{9223372036854775808: 1}"),
+    ("2 ** 100", "1267650600228229401496703205376"),
+    ("25!", "15511210043330985984000000"),
+    (
+      "1 << 63 >> 63 ^ 99999999999999999999",
+      "-100000000000000000000",
+    ),
+    ("99999999999999999999 % 7", "1"),
+    ("~99999999999999999999", "-100000000000000000000"),
+    ("99999999999999999999 >> 1", "49999999999999999999"),
+    (
+      "9223372036854775808 ** 2",
+      "85070591730234615865843651857942052864",
+    ),
+  ];
+
+  for (input, expected) in cases {
+    assert_eq!(
+      eval!(input).map(|result| result.to_string()),
+      Ok(expected.to_string()),
+      "{}",
+      input
+    )
+  }
+
+  // results which fit are ints again
+  let cases = vec![
+    ("-9223372036854775808", i64::MIN),
+    ("(9223372036854775807 + 1) - 1", i64::MAX),
+    ("99999999999999999999 / 99999999999999999999", 1),
+  ];
+
+  for (input, expected) in cases {
+    assert_eq!(eval!(input), Ok(Object::Int(expected)), "{}", input)
+  }
+
+  let cases = vec![
+    ("9223372036854775808 > 9223372036854775807", true),
+    ("-9223372036854775809 < -9223372036854775808", true),
+    ("9223372036854775807 + 1 == 9223372036854775808", true),
+    ("9223372036854775808 == 9223372036854775807", false),
+    ("9223372036854775808 == 9223372036854775808.0", true),
+    ("9223372036854775808 < 1e100", true),
+    ("[9223372036854775807 + 1] == [9223372036854775808]", true),
+    ("{9223372036854775808: true}[9223372036854775807 + 1]", true),
+    ("!9223372036854775808", false),
+    ("9223372036854775808 << 1 == 18446744073709551616", true),
+  ];
+
+  for (input, expected) in cases {
+    assert_eq!(eval!(input), Ok(Object::Bool(expected)), "{}", input)
+  }
+
+  assert_eq!(
+    eval!("2 ** 9223372036854775807"),
+    Err("Exponent too large: 2 ** 9223372036854775807".to_string())
+  );
+  assert_eq!(
+    eval!("10001!"),
+    Err("Factorial of a too large number: 10001!".to_string())
+  );
+  assert_eq!(
+    eval!("9223372036854775807!"),
+    Err("Factorial of a too large number: 9223372036854775807!".to_string())
+  );
+  assert_eq!(eval!("10000! > 0"), Ok(Object::Bool(true)));
+  assert_eq!(
+    eval!("99999999999999999999 / 0"),
+    Err("Division by zero: 99999999999999999999 / 0".to_string())
+  );
+  assert_eq!(eval!("99999999999999999999 + 0.5"), Ok(Object::Float(1e20)));
+}
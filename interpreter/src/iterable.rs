@@ -0,0 +1,68 @@
+use super::object::{HashKey, Object, Range};
+use std::collections::BTreeMap;
+
+/// A step of an iteration, a position and an element for sequences, or a key
+/// and its value for hashes.
+pub type Entry = (Object, Object);
+
+/// The iteration protocol of `for` loops. Any object can be iterated over by
+/// implementing it for its value, and returning it from `Object::as_iterable`.
+pub trait Iterable {
+  fn entries(&self) -> Box<dyn Iterator<Item = Entry> + '_>;
+
+  /// What `for (x in xs)` binds for an entry, the element by default.
+  fn item(&self, entry: Entry) -> Object {
+    entry.1
+  }
+}
+
+/// Elements are cloned as they're reached, not up front.
+impl Iterable for Vec<Object> {
+  fn entries(&self) -> Box<dyn Iterator<Item = Entry> + '_> {
+    Box::new(self.iter().cloned().enumerate().map(position))
+  }
+}
+
+/// Strings are iterated over by characters, each of which is a string.
+impl Iterable for String {
+  fn entries(&self) -> Box<dyn Iterator<Item = Entry> + '_> {
+    Box::new(
+      self
+        .chars()
+        .map(|ch| Object::Str(ch.to_string()))
+        .enumerate()
+        .map(position),
+    )
+  }
+}
+
+impl Iterable for Range {
+  fn entries(&self) -> Box<dyn Iterator<Item = Entry> + '_> {
+    let values: Box<dyn Iterator<Item = i64>> = if self.inclusive {
+      Box::new(self.start..=self.end)
+    } else {
+      Box::new(self.start..self.end)
+    };
+    Box::new(values.map(Object::Int).enumerate().map(position))
+  }
+}
+
+/// Hashes are iterated over in the order of their keys, like they're
+/// displayed.
+impl Iterable for BTreeMap<HashKey, Object> {
+  fn entries(&self) -> Box<dyn Iterator<Item = Entry> + '_> {
+    Box::new(
+      self
+        .iter()
+        .map(|(key, value)| (key.to_object(), value.clone())),
+    )
+  }
+
+  fn item(&self, entry: Entry) -> Object {
+    entry.0
+  }
+}
+
+fn position((index, element): (usize, Object)) -> Entry {
+  (Object::Int(index as i64), element)
+}
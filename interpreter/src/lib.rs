@@ -1,3 +1,5 @@
 mod environment;
 pub mod eval;
+mod iterable;
 mod object;
+pub mod repl;
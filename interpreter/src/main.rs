@@ -1,26 +1,30 @@
 use pratt_interpreter::eval;
-use pratt_parsing::lexer::Lexer;
-use pratt_parsing::parser::Parser;
+use pratt_interpreter::repl::Repl;
+use pratt_parsing::checker::{Checker, Redeclaration};
+use pratt_parsing::diagnostic::Renderer;
 use rustyline::error::ReadlineError as ReadLineError;
 use rustyline::Editor;
+use std::io::IsTerminal;
 
 fn main() {
   let mut rl = Editor::<()>::new();
+  let renderer = if std::io::stdout().is_terminal() && std::env::var_os("NO_COLOR").is_none() {
+    Renderer::colored()
+  } else {
+    Renderer::plain()
+  };
+  let options = Options::from_args();
+  let mut repl = Repl::new(
+    eval::Evaluator::new().with_arithmetic_mode(options.arithmetic_mode),
+    Checker::new().with_redeclaration(options.redeclaration),
+    renderer,
+  );
 
   loop {
     match rl.readline(">> ") {
       Ok(line) => {
         rl.add_history_entry(&line);
-
-        let mut parser = Parser::new(Lexer::new(line.as_str()));
-        let parser = parser.parse();
-
-        for error in parser.get_errors() {
-          println!("{}", error);
-        }
-
-        let evaluated = eval::Evaluator::new().eval(parser.get_stmts());
-        println!("{}", evaluated.unwrap());
+        print!("{}", repl.eval_line(&line));
       }
       Err(ReadLineError::Interrupted) => {
         break;
@@ -34,3 +38,30 @@ fn main() {
     }
   }
 }
+
+/// Integer arithmetic is checked unless `--wrapping` or `--saturating` is
+/// passed. Redeclaring a name in the same scope is allowed unless
+/// `--warn-redeclaration` or `--deny-redeclaration` is passed.
+struct Options {
+  arithmetic_mode: eval::ArithmeticMode,
+  redeclaration: Redeclaration,
+}
+
+impl Options {
+  fn from_args() -> Self {
+    let mut options = Options {
+      arithmetic_mode: eval::ArithmeticMode::Checked,
+      redeclaration: Redeclaration::Allow,
+    };
+    for arg in std::env::args().skip(1) {
+      match arg.as_str() {
+        "--wrapping" => options.arithmetic_mode = eval::ArithmeticMode::Wrapping,
+        "--saturating" => options.arithmetic_mode = eval::ArithmeticMode::Saturating,
+        "--warn-redeclaration" => options.redeclaration = Redeclaration::Warn,
+        "--deny-redeclaration" => options.redeclaration = Redeclaration::Error,
+        _ => eprintln!("Ignoring unknown argument: {}", arg),
+      }
+    }
+    options
+  }
+}
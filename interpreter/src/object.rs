@@ -1,73 +1,690 @@
+use super::environment::Environment;
+use super::eval::RuntimeError;
+use super::iterable::Iterable;
+#[cfg(feature = "bigint")]
+use num_bigint::BigInt;
+#[cfg(feature = "bigint")]
+use num_traits::{ToPrimitive, Zero};
+use pratt_parsing::ast;
+use std::cell::RefCell;
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
 use std::fmt;
-use std::ops::{Add, Div, Mul, Sub};
+use std::ops::{Add, BitAnd, BitOr, BitXor, Div, Mul, Rem, Shl, Shr, Sub};
+use std::rc::Rc;
 
-#[derive(PartialEq, Clone, Debug, PartialOrd)]
+#[derive(PartialEq, Clone, Debug)]
 pub enum Object {
   Int(i64),
+  /// An integer which doesn't fit into an `Int`. Integers are normalized, see
+  /// `Object::from_bigint`, so both variants never hold the same value.
+  #[cfg(feature = "bigint")]
+  BigInt(BigInt),
+  Float(f64),
   Bool(bool),
+  Str(String),
+  Array(Vec<Object>),
+  Hash(BTreeMap<HashKey, Object>),
+  Range(Range),
+  Function(Function),
   ReturnValue(Box<Object>),
+  /// Unwinds to the innermost loop and leaves it, see `ReturnValue`.
+  Break,
+  /// Unwinds to the innermost loop and starts its next iteration.
+  Continue,
 
-  Error(String),
+  /// A runtime error, which unwinds to the top level.
+  Error(RuntimeError),
   None,
 }
 
+/// The subset of objects which can be used as hash keys. Keys are ordered, so
+/// that hashes are always displayed in the same order.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Debug)]
+pub enum HashKey {
+  Int(i64),
+  #[cfg(feature = "bigint")]
+  BigInt(BigInt),
+  Bool(bool),
+  Str(String),
+}
+
+impl HashKey {
+  /// Make a key out of `object`, or return `None` if it isn't hashable.
+  pub fn from_object(object: &Object) -> Option<Self> {
+    match object {
+      Object::Int(val) => Some(HashKey::Int(*val)),
+      #[cfg(feature = "bigint")]
+      Object::BigInt(val) => Some(HashKey::BigInt(val.clone())),
+      Object::Bool(val) => Some(HashKey::Bool(*val)),
+      Object::Str(val) => Some(HashKey::Str(val.clone())),
+      _ => None,
+    }
+  }
+
+  pub fn to_object(&self) -> Object {
+    match self {
+      HashKey::Int(val) => Object::Int(*val),
+      #[cfg(feature = "bigint")]
+      HashKey::BigInt(val) => Object::BigInt(val.clone()),
+      HashKey::Bool(val) => Object::Bool(*val),
+      HashKey::Str(val) => Object::Str(val.clone()),
+    }
+  }
+}
+
+impl fmt::Display for HashKey {
+  fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      HashKey::Int(val) => write!(formatter, "{}", val),
+      #[cfg(feature = "bigint")]
+      HashKey::BigInt(val) => write!(formatter, "{}", val),
+      HashKey::Bool(val) => write!(formatter, "{}", val),
+      HashKey::Str(val) => write!(formatter, "{:?}", val),
+    }
+  }
+}
+
+/// A function value. It captures the environment it was defined in, which is
+/// what makes closures and recursive bindings work.
+#[derive(Clone)]
+pub struct Function {
+  pub params: Vec<ast::Ident>,
+  pub body: ast::BlockStatement,
+  pub env: Rc<RefCell<Environment>>,
+}
+
+impl PartialEq for Function {
+  fn eq(&self, other: &Self) -> bool {
+    self.params == other.params && self.body == other.body && Rc::ptr_eq(&self.env, &other.env)
+  }
+}
+
+// The captured environment usually contains the function itself, so it's
+// omitted to avoid infinite recursion.
+impl fmt::Debug for Function {
+  fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+    formatter
+      .debug_struct("Function")
+      .field("params", &self.params)
+      .field("body", &self.body)
+      .finish()
+  }
+}
+
+impl fmt::Display for Function {
+  fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+    let params = self
+      .params
+      .iter()
+      .map(|ast::Ident(name)| &**name)
+      .collect::<Vec<_>>()
+      .join(", ");
+    write!(formatter, "fn({}) {{ ... }}", params)
+  }
+}
+
+/// `start..end`, or `start..=end` if it's `inclusive`. A range is empty if
+/// its start is above its end, or equal to it and the range isn't inclusive.
+#[derive(PartialEq, Clone, Debug)]
+pub struct Range {
+  pub start: i64,
+  pub end: i64,
+  pub inclusive: bool,
+}
+
+impl fmt::Display for Range {
+  fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+    let infix = if self.inclusive { "..=" } else { ".." };
+    write!(formatter, "{}{}{}", self.start, infix, self.end)
+  }
+}
+
 impl fmt::Display for Object {
   fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
     match *self {
       Object::Int(ref val) => write!(formatter, "{}", val),
+      #[cfg(feature = "bigint")]
+      Object::BigInt(ref val) => write!(formatter, "{}", val),
+      // always print a decimal point or exponent, so floats and ints can be
+      // told apart
+      Object::Float(val) => write!(formatter, "{:?}", val),
       Object::Bool(val) => write!(formatter, "{}", val),
+      Object::Str(ref val) => write!(formatter, "{:?}", val),
+      Object::Array(ref elements) => {
+        let elements = elements
+          .iter()
+          .map(|element| element.to_string())
+          .collect::<Vec<_>>()
+          .join(", ");
+        write!(formatter, "[{}]", elements)
+      }
+      Object::Hash(ref pairs) => {
+        let pairs = pairs
+          .iter()
+          .map(|(key, value)| format!("{}: {}", key, value))
+          .collect::<Vec<_>>()
+          .join(", ");
+        write!(formatter, "{{{}}}", pairs)
+      }
+      Object::Range(ref val) => write!(formatter, "{}", val),
+      Object::Function(ref val) => write!(formatter, "{}", val),
       Object::ReturnValue(ref val) => write!(formatter, "{}", val),
+      Object::Break => write!(formatter, "break"),
+      Object::Continue => write!(formatter, "continue"),
       Object::Error(ref val) => write!(formatter, "{}", val),
       Object::None => write!(formatter, "None"),
     }
   }
 }
 
+/// The largest `n` whose factorial is promoted to a `BigInt`. Beyond it,
+/// computing `n!` would take very long, so it's an error instead.
+#[cfg(feature = "bigint")]
+const MAX_BIGINT_FACTORIAL: i64 = 10_000;
+
+/// How integer arithmetic treats results which don't fit into an `i64`.
+/// Dividing by zero is an error in every mode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ArithmeticMode {
+  /// Overflowing is a runtime error. With the `bigint` feature, results are
+  /// promoted to a `BigInt` instead.
+  #[default]
+  Checked,
+  /// Results wrap around at the boundaries of `i64`.
+  Wrapping,
+  /// Results are clamped to `i64::MIN` and `i64::MAX`.
+  Saturating,
+}
+
+/// Runtime errors spell operators in ASCII, unlike `ast::Infix`.
+pub(crate) fn symbol(infix: &ast::Infix) -> String {
+  match infix {
+    ast::Infix::Multiply => "*".to_string(),
+    ast::Infix::Divide => "/".to_string(),
+    ast::Infix::NotEqual => "!=".to_string(),
+    ast::Infix::GreaterThanEqual => ">=".to_string(),
+    ast::Infix::LessThanEqual => "<=".to_string(),
+    infix => infix.to_string(),
+  }
+}
+
+type CheckedOp = fn(i64, i64) -> Option<i64>;
+type UncheckedOp = fn(i64, i64) -> i64;
+
+/// `base ** exp` by repeated squaring, with `mul` doing the multiplications.
+/// Unlike `i64::pow`, the exponent isn't limited to `u32`.
+fn pow_with(mut base: i64, mut exp: i64, mul: CheckedOp) -> Option<i64> {
+  let mut result = 1;
+  loop {
+    if exp & 1 == 1 {
+      result = mul(result, base)?;
+    }
+    exp >>= 1;
+    if exp == 0 {
+      return Some(result);
+    }
+    base = mul(base, base)?;
+  }
+}
+
+fn checked_pow(base: i64, exp: i64) -> Option<i64> {
+  pow_with(base, exp, i64::checked_mul)
+}
+
+fn wrapping_pow(base: i64, exp: i64) -> i64 {
+  pow_with(base, exp, |left, right| Some(left.wrapping_mul(right))).unwrap_or_default()
+}
+
+fn saturating_pow(base: i64, exp: i64) -> i64 {
+  pow_with(base, exp, |left, right| Some(left.saturating_mul(right))).unwrap_or_default()
+}
+
+impl ArithmeticMode {
+  /// Apply `infix`, one of `+`, `-`, `*`, `/`, `%` or `**`, to two integers.
+  pub fn apply(self, left: i64, infix: &ast::Infix, right: i64) -> Object {
+    use ast::Infix;
+    let (checked, wrapping, saturating): (CheckedOp, UncheckedOp, UncheckedOp) = match infix {
+      Infix::Plus => (i64::checked_add, i64::wrapping_add, i64::saturating_add),
+      Infix::Minus => (i64::checked_sub, i64::wrapping_sub, i64::saturating_sub),
+      Infix::Multiply => (i64::checked_mul, i64::wrapping_mul, i64::saturating_mul),
+      Infix::Divide if right == 0 => {
+        return Object::error(format!("Division by zero: {} / {}", left, right))
+      }
+      Infix::Divide => (i64::checked_div, i64::wrapping_div, i64::saturating_div),
+      Infix::Modulo if right == 0 => {
+        return Object::error(format!("Division by zero: {} % {}", left, right))
+      }
+      // only `i64::MIN % -1` overflows, and its remainder is 0 after all
+      Infix::Modulo => (i64::checked_rem, i64::wrapping_rem, i64::wrapping_rem),
+      Infix::Power if right < 0 => {
+        return Object::error(format!("Negative exponent: {} ** {}", left, right))
+      }
+      Infix::Power => (checked_pow, wrapping_pow, saturating_pow),
+      _ => {
+        return Object::error(format!(
+          "Couldn't perform operation: {} {} {}",
+          left,
+          symbol(infix),
+          right
+        ))
+      }
+    };
+    let result = match self {
+      ArithmeticMode::Checked => checked(left, right),
+      ArithmeticMode::Wrapping => Some(wrapping(left, right)),
+      ArithmeticMode::Saturating => Some(saturating(left, right)),
+    };
+    match result {
+      Some(val) => Object::Int(val),
+      #[cfg(feature = "bigint")]
+      None => Object::big_arithmetic(left.into(), infix, right.into()),
+      #[cfg(not(feature = "bigint"))]
+      None => Object::error(format!(
+        "Integer overflow: {} {} {}",
+        left,
+        symbol(infix),
+        right
+      )),
+    }
+  }
+
+  /// `n!`, which is only defined for non-negative integers.
+  pub fn factorial(self, n: i64) -> Object {
+    if n < 0 {
+      return Object::error(format!("Factorial of a negative number: {}!", n));
+    }
+    let mut result: i64 = 1;
+    for k in 2..=n {
+      result = match self {
+        ArithmeticMode::Checked => match result.checked_mul(k) {
+          Some(val) => val,
+          #[cfg(feature = "bigint")]
+          None if n > MAX_BIGINT_FACTORIAL => {
+            return Object::error(format!("Factorial of a too large number: {}!", n))
+          }
+          #[cfg(feature = "bigint")]
+          None => return Object::BigInt((k..=n).fold(BigInt::from(result), |acc, k| acc * k)),
+          #[cfg(not(feature = "bigint"))]
+          None => return Object::error(format!("Integer overflow: {}!", n)),
+        },
+        // once the result is 0 or saturated, it stays so
+        ArithmeticMode::Wrapping if result == 0 => break,
+        ArithmeticMode::Wrapping => result.wrapping_mul(k),
+        ArithmeticMode::Saturating if result == i64::MAX => break,
+        ArithmeticMode::Saturating => result.saturating_mul(k),
+      };
+    }
+    Object::Int(result)
+  }
+
+  /// Negate an integer, which overflows for `i64::MIN`.
+  pub fn negate(self, val: i64) -> Object {
+    let result = match self {
+      ArithmeticMode::Checked => val.checked_neg(),
+      ArithmeticMode::Wrapping => Some(val.wrapping_neg()),
+      ArithmeticMode::Saturating => Some(val.saturating_neg()),
+    };
+    match result {
+      Some(val) => Object::Int(val),
+      #[cfg(feature = "bigint")]
+      None => Object::BigInt(-BigInt::from(val)),
+      #[cfg(not(feature = "bigint"))]
+      None => Object::error(format!("Integer overflow: -({})", val)),
+    }
+  }
+}
+
+impl Object {
+  pub(crate) fn error(message: String) -> Self {
+    Object::Error(RuntimeError::new(message))
+  }
+
+  /// A number as a float, ints are converted.
+  fn to_float(&self) -> Option<f64> {
+    match self {
+      Object::Int(val) => Some(*val as f64),
+      #[cfg(feature = "bigint")]
+      Object::BigInt(val) => val.to_f64(),
+      Object::Float(val) => Some(*val),
+      _ => None,
+    }
+  }
+
+  /// Both operands as floats if they're numbers and at least one of them is a
+  /// float, ints are converted.
+  fn float_operands(&self, rhs: &Self) -> Option<(f64, f64)> {
+    match (self, rhs) {
+      (Object::Float(_), _) | (_, Object::Float(_)) => Some((self.to_float()?, rhs.to_float()?)),
+      _ => None,
+    }
+  }
+
+  /// Both operands as big integers if they're integers and at least one of
+  /// them is a `BigInt`.
+  #[cfg(feature = "bigint")]
+  fn big_operands(&self, rhs: &Self) -> Option<(BigInt, BigInt)> {
+    match (self, rhs) {
+      (Object::BigInt(left_val), Object::BigInt(right_val)) => {
+        Some((left_val.clone(), right_val.clone()))
+      }
+      (Object::BigInt(left_val), Object::Int(right_val)) => {
+        Some((left_val.clone(), BigInt::from(*right_val)))
+      }
+      (Object::Int(left_val), Object::BigInt(right_val)) => {
+        Some((BigInt::from(*left_val), right_val.clone()))
+      }
+      _ => None,
+    }
+  }
+
+  /// An `Int` if `val` fits into one, otherwise a `BigInt`.
+  #[cfg(feature = "bigint")]
+  pub fn from_bigint(val: BigInt) -> Self {
+    i64::try_from(&val).map_or(Object::BigInt(val), Object::Int)
+  }
+
+  /// Apply `infix`, one of `+`, `-`, `*`, `/`, `%` or `**`, to two big
+  /// integers. Division truncates toward zero, like it does for ints.
+  #[cfg(feature = "bigint")]
+  fn big_arithmetic(left: BigInt, infix: &ast::Infix, right: BigInt) -> Self {
+    use ast::Infix;
+    let result = match infix {
+      Infix::Plus => left + right,
+      Infix::Minus => left - right,
+      Infix::Multiply => left * right,
+      Infix::Divide if right.is_zero() => {
+        return Object::error(format!("Division by zero: {} / {}", left, right))
+      }
+      Infix::Divide => left / right,
+      Infix::Modulo if right.is_zero() => {
+        return Object::error(format!("Division by zero: {} % {}", left, right))
+      }
+      Infix::Modulo => left % right,
+      Infix::Power => match u32::try_from(&right) {
+        Ok(exp) => left.pow(exp),
+        Err(_) if right.sign() == num_bigint::Sign::Minus => {
+          return Object::error(format!("Negative exponent: {} ** {}", left, right))
+        }
+        Err(_) => return Object::error(format!("Exponent too large: {} ** {}", left, right)),
+      },
+      _ => {
+        return Object::error(format!(
+          "Couldn't perform operation: {} {} {}",
+          left,
+          symbol(infix),
+          right
+        ))
+      }
+    };
+    Object::from_bigint(result)
+  }
+
+  /// Apply `infix`, one of `+`, `-`, `*`, `/`, `%` or `**`, to two numbers
+  /// which aren't both ints. Ints are promoted to big integers or floats, whichever the
+  /// other operand is.
+  fn mixed_arithmetic(self, infix: &ast::Infix, rhs: Self) -> Self {
+    use ast::Infix;
+    #[cfg(feature = "bigint")]
+    if let Some((left_val, right_val)) = self.big_operands(&rhs) {
+      return Object::big_arithmetic(left_val, infix, right_val);
+    }
+    match self.float_operands(&rhs) {
+      Some((left_val, right_val)) => Object::Float(match infix {
+        Infix::Plus => left_val + right_val,
+        Infix::Minus => left_val - right_val,
+        Infix::Multiply => left_val * right_val,
+        Infix::Power => left_val.powf(right_val),
+        // the remainder has the sign of the dividend, like for ints
+        Infix::Modulo => left_val % right_val,
+        // float division follows IEEE 754, dividing by zero gives an infinity
+        // or NaN
+        _ => left_val / right_val,
+      }),
+      None => Object::error(format!(
+        "Couldn't perform operation: {} {} {}",
+        self,
+        symbol(infix),
+        rhs
+      )),
+    }
+  }
+
+  /// Apply `infix`, one of `&`, `|`, `^`, `<<` or `>>`, to two integers.
+  /// Shifts are arithmetic, and shift counts must be less than 64.
+  fn bitwise(&self, infix: &ast::Infix, rhs: &Self) -> Self {
+    use ast::Infix;
+    let error =
+      |message: &str| Object::error(format!("{}: {} {} {}", message, self, symbol(infix), rhs));
+
+    if let Infix::ShiftLeft | Infix::ShiftRight = infix {
+      // `Err` tells whether the shift count is negative
+      let count = match rhs {
+        Object::Int(count) => u32::try_from(*count)
+          .ok()
+          .filter(|count| *count < i64::BITS)
+          .ok_or(*count < 0),
+        #[cfg(feature = "bigint")]
+        Object::BigInt(count) => Err(count.sign() == num_bigint::Sign::Minus),
+        _ => return error("Couldn't perform operation"),
+      };
+      return match (self, count) {
+        (_, Err(true)) => error("Negative shift count"),
+        (_, Err(false)) => error("Shift count too large"),
+        (Object::Int(val), Ok(count)) if *infix == Infix::ShiftLeft => Object::Int(val << count),
+        (Object::Int(val), Ok(count)) => Object::Int(val >> count),
+        #[cfg(feature = "bigint")]
+        (Object::BigInt(val), Ok(count)) if *infix == Infix::ShiftLeft => {
+          Object::from_bigint(val << count)
+        }
+        #[cfg(feature = "bigint")]
+        (Object::BigInt(val), Ok(count)) => Object::from_bigint(val >> count),
+        _ => error("Couldn't perform operation"),
+      };
+    }
+
+    #[cfg(feature = "bigint")]
+    if let Some((left_val, right_val)) = self.big_operands(rhs) {
+      return Object::from_bigint(match infix {
+        Infix::BitAnd => left_val & right_val,
+        Infix::BitOr => left_val | right_val,
+        _ => left_val ^ right_val,
+      });
+    }
+    match (self, rhs) {
+      (Object::Int(left_val), Object::Int(right_val)) => Object::Int(match infix {
+        Infix::BitAnd => left_val & right_val,
+        Infix::BitOr => left_val | right_val,
+        _ => left_val ^ right_val,
+      }),
+      _ => error("Couldn't perform operation"),
+    }
+  }
+
+  /// Order two numbers or two strings. Ints are converted to floats when
+  /// compared to a float, and NaN is unordered, so every comparison involving
+  /// it is false. `Err` means that the operands aren't comparable at all.
+  pub fn compare(&self, other: &Self) -> Result<Option<Ordering>, ()> {
+    match (self, other) {
+      (Object::Int(left_val), Object::Int(right_val)) => Ok(Some(left_val.cmp(right_val))),
+      (Object::Str(left_val), Object::Str(right_val)) => Ok(Some(left_val.cmp(right_val))),
+      (left, right) => {
+        #[cfg(feature = "bigint")]
+        if let Some((left_val, right_val)) = left.big_operands(right) {
+          return Ok(Some(left_val.cmp(&right_val)));
+        }
+        match left.float_operands(right) {
+          Some((left_val, right_val)) => Ok(left_val.partial_cmp(&right_val)),
+          None => Err(()),
+        }
+      }
+    }
+  }
+
+  /// Equality as defined by the language. Unlike `==` on `Object`, which is
+  /// structural, an int equals a float of the same value, and NaN doesn't
+  /// equal anything.
+  pub fn equals(&self, other: &Self) -> bool {
+    match (self, other) {
+      (Object::Array(left_val), Object::Array(right_val)) => {
+        left_val.len() == right_val.len()
+          && left_val
+            .iter()
+            .zip(right_val)
+            .all(|(left, right)| left.equals(right))
+      }
+      (Object::Hash(left_val), Object::Hash(right_val)) => {
+        left_val.len() == right_val.len()
+          && left_val
+            .iter()
+            .zip(right_val)
+            .all(|((left_key, left), (right_key, right))| {
+              left_key == right_key && left.equals(right)
+            })
+      }
+      (left, right) => match left.float_operands(right) {
+        Some((left_val, right_val)) => left_val == right_val,
+        None => left == right,
+      },
+    }
+  }
+
+  /// `self..rhs` or `self..=rhs`, whose bounds must be integers.
+  pub fn range(self, rhs: Self, inclusive: bool) -> Self {
+    match (self, rhs) {
+      (Object::Int(start), Object::Int(end)) => Object::Range(Range {
+        start,
+        end,
+        inclusive,
+      }),
+      (start, end) => {
+        let infix = if inclusive { "..=" } else { ".." };
+        Object::error(format!(
+          "Couldn't perform operation: {} {} {}",
+          start, infix, end
+        ))
+      }
+    }
+  }
+
+  /// How a `for` loop iterates over this object, if it can.
+  pub fn as_iterable(&self) -> Option<&dyn Iterable> {
+    match self {
+      Object::Array(elements) => Some(elements),
+      Object::Str(val) => Some(val),
+      Object::Range(range) => Some(range),
+      Object::Hash(pairs) => Some(pairs),
+      _ => None,
+    }
+  }
+}
+
+// integer arithmetic is checked, see `ArithmeticMode`
 impl Add for Object {
   type Output = Object;
   fn add(self, rhs: Object) -> Object {
-    if let Object::Int(left_val) = self {
-      if let Object::Int(right_val) = rhs {
-        return Object::Int(left_val + right_val);
+    match (self, rhs) {
+      (Object::Int(left_val), Object::Int(right_val)) => {
+        ArithmeticMode::Checked.apply(left_val, &ast::Infix::Plus, right_val)
       }
+      (Object::Str(left_val), Object::Str(right_val)) => Object::Str(left_val + &right_val),
+      (left, right) => left.mixed_arithmetic(&ast::Infix::Plus, right),
     }
-    // TODO: handle the addition for string type
-    Object::Error(format!("Couldn't perform operation: {} + {}", self, rhs,))
   }
 }
 
 impl Sub for Object {
   type Output = Object;
   fn sub(self, rhs: Object) -> Object {
-    if let Object::Int(left_val) = self {
-      if let Object::Int(right_val) = rhs {
-        return Object::Int(left_val - right_val);
+    match (self, rhs) {
+      (Object::Int(left_val), Object::Int(right_val)) => {
+        ArithmeticMode::Checked.apply(left_val, &ast::Infix::Minus, right_val)
       }
+      (left, right) => left.mixed_arithmetic(&ast::Infix::Minus, right),
     }
-    Object::Error(format!("Couldn't perform operation: {} - {}", self, rhs,))
   }
 }
 
 impl Mul for Object {
   type Output = Self;
   fn mul(self, rhs: Self) -> Self {
-    if let Object::Int(left_val) = self {
-      if let Object::Int(right_val) = rhs {
-        return Object::Int(left_val * right_val);
+    match (self, rhs) {
+      (Object::Int(left_val), Object::Int(right_val)) => {
+        ArithmeticMode::Checked.apply(left_val, &ast::Infix::Multiply, right_val)
       }
+      (left, right) => left.mixed_arithmetic(&ast::Infix::Multiply, right),
     }
-    Object::Error(format!("Couldn't perform operation: {} * {}", self, rhs,))
   }
 }
 
 impl Div for Object {
   type Output = Self;
   fn div(self, rhs: Self) -> Self {
-    if let Object::Int(left_val) = self {
-      if let Object::Int(right_val) = rhs {
-        return Object::Int(left_val / right_val);
+    match (self, rhs) {
+      (Object::Int(left_val), Object::Int(right_val)) => {
+        ArithmeticMode::Checked.apply(left_val, &ast::Infix::Divide, right_val)
       }
+      (left, right) => left.mixed_arithmetic(&ast::Infix::Divide, right),
     }
-    Object::Error(format!("Couldn't perform operation: {} / {}", self, rhs,))
+  }
+}
+
+impl Object {
+  /// `self ** rhs`, there's no operator trait for it. Like the other
+  /// operators, integer arithmetic is checked.
+  pub fn pow(self, rhs: Self) -> Self {
+    match (self, rhs) {
+      (Object::Int(left_val), Object::Int(right_val)) => {
+        ArithmeticMode::Checked.apply(left_val, &ast::Infix::Power, right_val)
+      }
+      (left, right) => left.mixed_arithmetic(&ast::Infix::Power, right),
+    }
+  }
+}
+
+impl Rem for Object {
+  type Output = Self;
+  fn rem(self, rhs: Self) -> Self {
+    match (self, rhs) {
+      (Object::Int(left_val), Object::Int(right_val)) => {
+        ArithmeticMode::Checked.apply(left_val, &ast::Infix::Modulo, right_val)
+      }
+      (left, right) => left.mixed_arithmetic(&ast::Infix::Modulo, right),
+    }
+  }
+}
+
+impl BitAnd for Object {
+  type Output = Self;
+  fn bitand(self, rhs: Self) -> Self {
+    self.bitwise(&ast::Infix::BitAnd, &rhs)
+  }
+}
+
+impl BitOr for Object {
+  type Output = Self;
+  fn bitor(self, rhs: Self) -> Self {
+    self.bitwise(&ast::Infix::BitOr, &rhs)
+  }
+}
+
+impl BitXor for Object {
+  type Output = Self;
+  fn bitxor(self, rhs: Self) -> Self {
+    self.bitwise(&ast::Infix::BitXor, &rhs)
+  }
+}
+
+impl Shl for Object {
+  type Output = Self;
+  fn shl(self, rhs: Self) -> Self {
+    self.bitwise(&ast::Infix::ShiftLeft, &rhs)
+  }
+}
+
+impl Shr for Object {
+  type Output = Self;
+  fn shr(self, rhs: Self) -> Self {
+    self.bitwise(&ast::Infix::ShiftRight, &rhs)
   }
 }
@@ -0,0 +1,64 @@
+#[cfg(test)]
+mod test;
+
+use crate::eval::Evaluator;
+use pratt_parsing::checker::Checker;
+use pratt_parsing::diagnostic::{Renderer, Severity, Source};
+use pratt_parsing::lexer::Lexer;
+use pratt_parsing::parser::Parser;
+
+/// A REPL session. One evaluator is kept for the whole session, so bindings
+/// made on a line are still visible on the following ones. The same goes for
+/// the checker.
+pub struct Repl {
+  evaluator: Evaluator,
+  checker: Checker,
+  renderer: Renderer,
+}
+
+impl Repl {
+  pub fn new(evaluator: Evaluator, checker: Checker, renderer: Renderer) -> Self {
+    Repl {
+      evaluator,
+      checker,
+      renderer,
+    }
+  }
+
+  /// Parse, check and evaluate `line`, and return what to print for it.
+  pub fn eval_line(&mut self, line: &str) -> String {
+    let mut parser = Parser::new(Lexer::new(line));
+    let parser = parser.parse();
+
+    let source = Source::new("repl", line);
+    let errors = parser.get_errors();
+    if !errors.is_empty() {
+      return errors
+        .iter()
+        .map(|error| self.renderer.render(&error.to_diagnostic(), &source))
+        .collect();
+    }
+
+    let stmts = parser.get_stmts();
+    let errors = self.checker.check(&stmts);
+    let mut output: String = errors
+      .iter()
+      .map(|error| self.renderer.render(&error.to_diagnostic(), &source))
+      .collect();
+    if errors
+      .iter()
+      .any(|error| error.severity() == Severity::Error)
+    {
+      return output;
+    }
+
+    match self.evaluator.eval(stmts) {
+      Ok(evaluated) => output += &format!("{}\n", evaluated),
+      Err(error) => {
+        self.checker.rollback();
+        output += &self.renderer.render(&error.to_diagnostic(), &source);
+      }
+    }
+    output
+  }
+}
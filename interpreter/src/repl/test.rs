@@ -0,0 +1,53 @@
+use super::*;
+use pratt_parsing::checker::Redeclaration;
+
+fn repl(redeclaration: Redeclaration) -> Repl {
+  Repl::new(
+    Evaluator::new(),
+    Checker::new().with_redeclaration(redeclaration),
+    Renderer::plain(),
+  )
+}
+
+#[test]
+fn forget_declarations_of_failed_lines() {
+  let mut repl = repl(Redeclaration::Error);
+  assert!(repl
+    .eval_line("const c = 1 / 0")
+    .starts_with("error: Division by zero: 1 / 0\n"));
+  assert_eq!(repl.eval_line("let c = 2"), "2\n");
+  assert_eq!(repl.eval_line("c = 5"), "5\n");
+  assert!(repl.eval_line("const c = 3").contains("error[E0016]"));
+}
+
+#[test]
+fn render_runtime_errors() {
+  let mut repl = repl(Redeclaration::Allow);
+  assert_eq!(
+    repl.eval_line("let f = fn(a) { a[1] }; f([0]) + 1"),
+    "\
+error: Index out of bounds: the length is 1 but the index is 1
+ --> repl:1:25
+  |
+1 | let f = fn(a) { a[1] }; f([0]) + 1
+  |                         ^^^^^^
+"
+  );
+  assert_eq!(
+    repl.eval_line("let x = 1 +\n  2 / 0"),
+    "\
+error: Division by zero: 2 / 0
+ --> repl:2:3
+  |
+2 |   2 / 0
+  |   ^^^^^
+"
+  );
+  assert_eq!(
+    repl.eval_line("break"),
+    "\
+error: Couldn't break outside of a loop
+ --> repl
+"
+  );
+}
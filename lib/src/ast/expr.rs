@@ -1,4 +1,4 @@
-use super::BlockStatement;
+use super::{BlockStatement, Pattern, Spanned};
 use std::fmt;
 
 /// An operator "in front of" its operand. Any expression can follow a prefix
@@ -10,6 +10,8 @@ pub enum Prefix {
   Bang,
   Minus,
   Plus,
+  /// `~`, which flips every bit of an integer
+  BitNot,
 }
 
 /// A literal is an expression consisting of a single token, rather than a
@@ -19,7 +21,12 @@ pub enum Prefix {
 #[derive(PartialEq, Debug, Clone)]
 pub enum Literal {
   Int(i64),
+  /// An integer which doesn't fit into an `Int`.
+  #[cfg(feature = "bigint")]
+  BigInt(num_bigint::BigInt),
+  Float(f64),
   Bool(bool),
+  Str(String),
 }
 
 impl fmt::Display for Prefix {
@@ -28,6 +35,7 @@ impl fmt::Display for Prefix {
       Prefix::Plus => "+",
       Prefix::Minus => "-",
       Prefix::Bang => "!",
+      Prefix::BitNot => "~",
     };
     write!(formatter, "{}", literal)
   }
@@ -41,12 +49,25 @@ pub enum Infix {
   Minus,
   Multiply,
   Divide,
+  Modulo,
+  Power,
+  BitAnd,
+  BitOr,
+  BitXor,
+  ShiftLeft,
+  ShiftRight,
   GreaterThan,
   LessThan,
   Equal,
   NotEqual,
   GreaterThanEqual,
   LessThanEqual,
+  And,
+  Or,
+  /// `a..b`, which excludes its end
+  Range,
+  /// `a..=b`, which includes its end
+  RangeInclusive,
 }
 
 impl fmt::Display for Infix {
@@ -56,66 +77,212 @@ impl fmt::Display for Infix {
       Infix::Minus => "-",
       Infix::Multiply => "×",
       Infix::Divide => "÷",
+      Infix::Modulo => "%",
+      Infix::Power => "**",
+      Infix::BitAnd => "&",
+      Infix::BitOr => "|",
+      Infix::BitXor => "^",
+      Infix::ShiftLeft => "<<",
+      Infix::ShiftRight => ">>",
       Infix::GreaterThan => ">",
       Infix::LessThan => "<",
       Infix::Equal => "==",
       Infix::NotEqual => "≠",
       Infix::GreaterThanEqual => "≥",
       Infix::LessThanEqual => "≤",
+      Infix::And => "&&",
+      Infix::Or => "||",
+      Infix::Range => "..",
+      Infix::RangeInclusive => "..=",
     };
     write!(formatter, "{}", literal)
   }
 }
 
 /// An operator "after" its operand
+///
+/// `<expression><postfix operator>`
 #[derive(PartialEq, Debug, Clone)]
-pub enum Postfix {}
+pub enum Postfix {
+  /// `n!`
+  Factorial,
+  /// `a?`, which returns early from the enclosing function if its operand
+  /// has no value.
+  Try,
+}
+
+impl fmt::Display for Postfix {
+  fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+    let literal = match *self {
+      Postfix::Factorial => "!",
+      Postfix::Try => "?",
+    };
+    write!(formatter, "{}", literal)
+  }
+}
 
 #[derive(PartialEq, PartialOrd, Debug, Clone)]
 pub enum Precedence {
   Lowest,
+  /// `=` or a compound assignment like `+=`
+  Assign,
+  /// `..` or `..=`, which binds looser than any other operator but
+  /// assignments, so that `0..n + 1` is `0..(n + 1)`
+  Range,
+  /// ||
+  LogicalOr,
+  /// &&
+  LogicalAnd,
+  /// |
+  BitOr,
+  /// ^
+  BitXor,
+  /// &, which binds looser than comparisons like in C, so that `a & b == c`
+  /// is `a & (b == c)`
+  BitAnd,
   /// ==
   Equals,
-  /// `>` or `<`
+  /// `<`, `>`, `<=` or `>=`
   LessGreater,
+  /// `<<` or `>>`
+  Shift,
   /// `+` or `-`
   Sum,
-  /// `*` or `/`
+  /// `*`, `/` or `%`
   Product,
   /// -a or !a
   Prefix,
+  /// a ** b, which binds tighter than a prefix, so that `-a ** b` is
+  /// `-(a ** b)`
+  Power,
+  /// a! or a?
+  Postfix,
   /// foo()
   Call,
   /// slice[index]
   Index,
 }
 
+impl Precedence {
+  /// The precedence right below this one. Right-associative operators parse
+  /// their right operand with it, so that it takes operators of the same
+  /// precedence along.
+  pub fn lower(&self) -> Precedence {
+    match self {
+      Precedence::Lowest | Precedence::Assign => Precedence::Lowest,
+      Precedence::Range => Precedence::Assign,
+      Precedence::LogicalOr => Precedence::Range,
+      Precedence::LogicalAnd => Precedence::LogicalOr,
+      Precedence::BitOr => Precedence::LogicalAnd,
+      Precedence::BitXor => Precedence::BitOr,
+      Precedence::BitAnd => Precedence::BitXor,
+      Precedence::Equals => Precedence::BitAnd,
+      Precedence::LessGreater => Precedence::Equals,
+      Precedence::Shift => Precedence::LessGreater,
+      Precedence::Sum => Precedence::Shift,
+      Precedence::Product => Precedence::Sum,
+      Precedence::Prefix => Precedence::Product,
+      Precedence::Power => Precedence::Prefix,
+      Precedence::Postfix => Precedence::Power,
+      Precedence::Call => Precedence::Postfix,
+      Precedence::Index => Precedence::Call,
+    }
+  }
+}
+
+/// How a sequence of operators of the same precedence groups.
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum Associativity {
+  /// `a - b - c` is `(a - b) - c`
+  Left,
+  /// `a ** b ** c` is `a ** (b ** c)`, or `a = b = c` is `a = (b = c)`
+  Right,
+}
+
+/// The loop variables of a `for` loop.
+#[derive(PartialEq, Debug, Clone)]
+pub enum ForBinding {
+  /// `for (x in xs)`, bound to the elements of a sequence or the keys of a
+  /// hash
+  Item(Spanned<super::Ident>),
+  /// `for (k, v in xs)`, bound to the positions and elements of a sequence or
+  /// the keys and values of a hash
+  Entry(Spanned<super::Ident>, Spanned<super::Ident>),
+}
+
+/// `<pattern> => <body>` or `<pattern> if <guard> => <body>`, the body is
+/// only evaluated if the guard holds as well. The body is an expression or a
+/// `{ ... }` block, an expression is kept as a block of its own.
+#[derive(PartialEq, Debug, Clone)]
+pub struct MatchArm {
+  pub pattern: Spanned<Pattern>,
+  pub guard: Option<Spanned<Expr>>,
+  pub body: super::BlockStatement,
+}
+
+/// Every sub-expression is wrapped in `Spanned`, so each node of the tree knows
+/// where it comes from.
 #[derive(PartialEq, Debug, Clone)]
-pub enum Expr<'ident> {
-  Ident(super::Ident<'ident>),
+pub enum Expr {
+  Ident(super::Ident),
   /// Literal expression directly describes a number, character, string or
   /// boolean value.
   Literal(super::Literal),
   /// `<prefix operator><expression>`
-  Prefix(Prefix, Box<Expr<'ident>>),
+  Prefix(Prefix, Box<Spanned<Expr>>),
+  /// `<expression><postfix operator>`
+  Postfix(Box<Spanned<Expr>>, Postfix),
   /// `<expression><infix operator><expression>`
-  Infix(Box<Expr<'ident>>, Infix, Box<Expr<'ident>>),
+  Infix(Box<Spanned<Expr>>, Infix, Box<Spanned<Expr>>),
   /// `if (<condition>) <consequence> else <alternative>`
   If {
-    condition: Box<Expr<'ident>>,
-    consequence: BlockStatement<'ident>,
-    alternative: Option<BlockStatement<'ident>>,
+    condition: Box<Spanned<Expr>>,
+    consequence: BlockStatement,
+    alternative: Option<BlockStatement>,
+  },
+  /// `while (<condition>) <body>`
+  While {
+    condition: Box<Spanned<Expr>>,
+    body: BlockStatement,
+  },
+  /// `for (<binding> in <iterable>) <body>`
+  For {
+    binding: ForBinding,
+    iterable: Box<Spanned<Expr>>,
+    body: BlockStatement,
+  },
+  /// `match (<subject>) { <comma separated arms> }`, see `MatchArm`
+  Match {
+    subject: Box<Spanned<Expr>>,
+    arms: Vec<MatchArm>,
+  },
+  /// `<target> = <value>`, or `<target> <operator>= <value>` which applies
+  /// `operator` to the current value of `target` and `value`. Targets are
+  /// identifiers or index expressions of them, e.g. `a[i][j]`.
+  Assign {
+    target: Box<Spanned<Expr>>,
+    operator: Option<Infix>,
+    value: Box<Spanned<Expr>>,
   },
   /// `fn <parameters> <block statements>`
   Function {
-    params: Vec<super::Ident<'ident>>,
-    body: BlockStatement<'ident>,
+    params: Vec<Spanned<super::Ident>>,
+    body: BlockStatement,
   },
   /// `<expression>(<comma separated expressions>)`
   Call {
-    function: Box<Expr<'ident>>,
+    function: Box<Spanned<Expr>>,
     // `Vec<T>` is already on the heap, `Box<Vec<T>>` makes an extra allocation
     // https://rust-lang.github.io/rust-clippy/master/index.html#box_collection
-    arguments: Vec<Expr<'ident>>,
+    arguments: Vec<Spanned<Expr>>,
+  },
+  /// `[<comma separated expressions>]`
+  Array(Vec<Spanned<Expr>>),
+  /// `{<comma separated <expression>: <expression> pairs>}`
+  Hash(Vec<(Spanned<Expr>, Spanned<Expr>)>),
+  /// `<expression>[<expression>]`
+  Index {
+    left: Box<Spanned<Expr>>,
+    index: Box<Spanned<Expr>>,
   },
 }
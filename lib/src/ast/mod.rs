@@ -1,11 +1,24 @@
 mod expr;
+mod pattern;
 mod stmt;
 
 pub use self::expr::*;
+pub use self::pattern::*;
 pub use self::stmt::*;
+pub use crate::span::{Span, Spanned};
+use std::rc::Rc;
 
 /// It represents a kind of AST node, unlike `crate::token::Token::Ident(&'a str)` which represents a kind of Token type.
+///
+/// Unlike tokens, the AST owns its identifiers, so it can outlive the source
+/// it's parsed from, e.g. a function defined on a REPL line.
 #[derive(PartialEq, Debug, Clone)]
-pub struct Ident<'ident>(pub &'ident str);
+pub struct Ident(pub Rc<str>);
 
-pub type Program<'ident> = Vec<Statement<'ident>>;
+impl From<&str> for Ident {
+  fn from(name: &str) -> Self {
+    Ident(name.into())
+  }
+}
+
+pub type Program = Vec<Spanned<Statement>>;
@@ -0,0 +1,31 @@
+use super::{Ident, Literal, Spanned};
+
+/// What a `match` arm compares its subject with. Patterns nest, and the
+/// identifiers in them are bound to the parts of the subject they match.
+#[derive(PartialEq, Debug, Clone)]
+pub enum Pattern {
+  /// `_`, which matches anything
+  Wildcard,
+  /// `<identifier>`, which matches anything and binds it
+  Binding(Ident),
+  /// Matches values equal to the literal, e.g. `1`, `-2.5` or `"a"`.
+  Literal(Literal),
+  /// `[<comma separated patterns>]`, which matches arrays of the same length,
+  /// or `[<comma separated patterns>, ..<rest>]`, which matches arrays at
+  /// least as long and matches `rest` against the remaining elements. `rest`
+  /// is a wildcard or a binding.
+  Array {
+    elements: Vec<Spanned<Pattern>>,
+    rest: Option<Box<Spanned<Pattern>>>,
+  },
+  /// `{<comma separated <literal>: <pattern> pairs>}`, which matches hashes
+  /// having all of the keys, and possibly others.
+  Hash(Vec<(Spanned<Literal>, Spanned<Pattern>)>),
+}
+
+impl Pattern {
+  /// Whether the pattern matches any value.
+  pub fn is_irrefutable(&self) -> bool {
+    matches!(self, Pattern::Wildcard | Pattern::Binding(_))
+  }
+}
@@ -1,9 +1,17 @@
 use super::expr;
+use super::Spanned;
 
 #[derive(PartialEq, Debug, Clone)]
-pub enum Statement<'ident> {
-  Let(super::Ident<'ident>, super::Expr<'ident>),
-  Return(super::Expr<'ident>),
+pub enum Statement {
+  Let(Spanned<super::Ident>, Spanned<super::Expr>),
+  /// `const <identifier> = <expression>`, a binding which can't be assigned
+  /// to.
+  Const(Spanned<super::Ident>, Spanned<super::Expr>),
+  Return(Spanned<super::Expr>),
+  /// Leave the innermost loop.
+  Break,
+  /// Skip to the next iteration of the innermost loop.
+  Continue,
   /// It's a statement that consists solely of one expression. and not really
   /// a distinct statement, and only a wrapper.
   /// We need it because it's totally legal in Monkey to write the following
@@ -16,7 +24,10 @@ pub enum Statement<'ident> {
   /// add it to the Statements slice of ast::Program
   ///
   /// [Similar implementation in rust: expression statement](https://doc.rust-lang.org/reference/statements.html#expression-statements)
-  Expr(expr::Expr<'ident>),
+  Expr(Spanned<expr::Expr>),
+  /// A malformed statement the parser skipped over. It's kept so the program
+  /// still covers the whole source, e.g. for editor tooling.
+  Error,
 }
 
-pub type BlockStatement<'ident> = Vec<Statement<'ident>>;
+pub type BlockStatement = Vec<Spanned<Statement>>;
@@ -0,0 +1,148 @@
+use crate::diagnostic::{Diagnostic, Label, Severity};
+use crate::span::Span;
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CheckErrorKind {
+  /// An assignment to `name`, or to an element of it, which is a constant
+  /// declared at `declared`. Declarations of earlier programs have no span.
+  AssignToConst {
+    name: String,
+    declared: Option<Span>,
+  },
+  /// A `let` or `const` of `name`, which is declared at `previous` in the same
+  /// scope already, see `Redeclaration`. Redeclaring a `constant` is always an
+  /// error.
+  Redeclaration {
+    name: String,
+    previous: Option<Span>,
+    constant: bool,
+  },
+  /// A `match` which may not match its subject, see `Checker`.
+  NonExhaustiveMatch,
+  /// A match arm which matches nothing the arm at `covered_by` doesn't.
+  UnreachableArm { covered_by: Span },
+}
+
+impl CheckErrorKind {
+  /// A stable code identifying the kind of error, like
+  /// `ParseErrorKind::code`. Codes are never shared with parse errors.
+  pub fn code(&self) -> &'static str {
+    match self {
+      CheckErrorKind::AssignToConst { .. } => "E0015",
+      CheckErrorKind::Redeclaration { .. } => "E0016",
+      CheckErrorKind::NonExhaustiveMatch => "E0017",
+      CheckErrorKind::UnreachableArm { .. } => "E0018",
+    }
+  }
+}
+
+impl fmt::Display for CheckErrorKind {
+  fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      CheckErrorKind::AssignToConst { name, .. } => {
+        write!(formatter, "cannot assign to constant `{}`", name)
+      }
+      CheckErrorKind::Redeclaration { name, .. } => {
+        write!(formatter, "`{}` is already declared in this scope", name)
+      }
+      CheckErrorKind::NonExhaustiveMatch => write!(formatter, "non-exhaustive match"),
+      CheckErrorKind::UnreachableArm { .. } => write!(formatter, "unreachable match arm"),
+    }
+  }
+}
+
+/// A problem found by the `Checker`. Unlike parse errors, some of them are
+/// only warnings, which don't keep a program from running.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CheckError {
+  kind: CheckErrorKind,
+  severity: Severity,
+  span: Span,
+}
+
+impl CheckError {
+  pub fn new(kind: CheckErrorKind, severity: Severity, span: Span) -> Self {
+    CheckError {
+      kind,
+      severity,
+      span,
+    }
+  }
+
+  pub fn kind(&self) -> &CheckErrorKind {
+    &self.kind
+  }
+
+  pub fn severity(&self) -> Severity {
+    self.severity
+  }
+
+  /// See `CheckErrorKind::code`.
+  pub fn code(&self) -> &'static str {
+    self.kind.code()
+  }
+
+  pub fn message(&self) -> String {
+    self.kind.to_string()
+  }
+
+  /// Where the error occurred in the source.
+  pub fn span(&self) -> Span {
+    self.span
+  }
+
+  pub fn to_diagnostic(&self) -> Diagnostic {
+    let mut diagnostic = Diagnostic::new(self.severity, self.message()).with_code(self.code());
+    match &self.kind {
+      CheckErrorKind::AssignToConst { name, declared } => {
+        diagnostic =
+          diagnostic.with_label(Label::primary(self.span, "cannot assign to a constant"));
+        if let Some(declared) = declared {
+          diagnostic =
+            diagnostic.with_label(Label::secondary(*declared, "declared as a constant here"));
+        }
+        diagnostic.with_help(format!(
+          "declare `{}` with `let` to make it assignable",
+          name
+        ))
+      }
+      CheckErrorKind::Redeclaration {
+        previous, constant, ..
+      } => {
+        diagnostic = diagnostic.with_label(Label::primary(self.span, "redeclared here"));
+        if let Some(previous) = previous {
+          diagnostic = diagnostic.with_label(Label::secondary(*previous, "first declared here"));
+        }
+        if *constant {
+          diagnostic
+            .with_help("pick another name")
+            .with_note("a constant can't be redeclared in the scope it's declared in")
+        } else if self.severity == Severity::Warning {
+          diagnostic.with_note("the new binding replaces the previous one")
+        } else {
+          diagnostic.with_help("assign to the existing binding instead, or pick another name")
+        }
+      }
+      CheckErrorKind::NonExhaustiveMatch => diagnostic
+        .with_label(Label::primary(self.span, "may not be matched by any arm"))
+        .with_help("add a `_ => ...` arm, which matches any value")
+        .with_note("only a catch-all arm, or arms for both `true` and `false`, match every value"),
+      CheckErrorKind::UnreachableArm { covered_by } => diagnostic
+        .with_label(Label::primary(self.span, "unreachable arm"))
+        .with_label(Label::secondary(*covered_by, "already matched by this arm")),
+    }
+  }
+}
+
+impl fmt::Display for CheckError {
+  fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+    write!(
+      formatter,
+      "{}[{}]: {}",
+      self.severity,
+      self.code(),
+      self.kind
+    )
+  }
+}
@@ -0,0 +1,400 @@
+mod error;
+#[cfg(test)]
+mod test;
+
+pub use error::{CheckError, CheckErrorKind};
+
+use crate::ast::{self, Expr, Literal, Pattern, Spanned, Statement};
+use crate::diagnostic::Severity;
+use crate::span::Span;
+use std::collections::HashMap;
+
+/// What to do about a `let` or `const` of a name which is declared in the same
+/// scope already. Declaring it in a nested scope always shadows the outer
+/// binding. Redeclaring a constant in its scope is an error either way.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Redeclaration {
+  /// The new binding silently replaces the previous one.
+  #[default]
+  Allow,
+  /// Like `Allow`, but report a warning.
+  Warn,
+  /// Report an error.
+  Error,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BindingKind {
+  /// `let` bindings, function parameters and loop variables
+  Variable,
+  Const,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Binding {
+  kind: BindingKind,
+  /// Where the name is declared, `None` if it's declared by an earlier
+  /// program, whose spans don't point into the current one.
+  span: Option<Span>,
+}
+
+/// Keys are owned, see `Environment` of the interpreter.
+type Scope = HashMap<String, Binding>;
+
+/// A static pass over a parsed program before it's evaluated. It resolves
+/// names the way the evaluator scopes them, to report assignments to
+/// constants and redeclarations in the same scope. It also warns about `match`
+/// arms which can never be reached, and `match`es which may not match their
+/// subject at all.
+///
+/// The global scope is kept between programs, so the declarations of one
+/// program are known when checking the next one, like in a REPL session.
+#[derive(Default)]
+pub struct Checker {
+  redeclaration: Redeclaration,
+  globals: Scope,
+  /// The global scope before the last checked program, to `rollback` to.
+  previous_globals: Scope,
+  /// The scopes nested in the global one, innermost last.
+  scopes: Vec<Scope>,
+  errors: Vec<CheckError>,
+}
+
+impl Checker {
+  pub fn new() -> Self {
+    Checker {
+      redeclaration: Redeclaration::default(),
+      globals: Scope::new(),
+      previous_globals: Scope::new(),
+      scopes: vec![],
+      errors: vec![],
+    }
+  }
+
+  pub fn with_redeclaration(mut self, redeclaration: Redeclaration) -> Self {
+    self.redeclaration = redeclaration;
+    self
+  }
+
+  /// Check `program` and return what's wrong with it, warnings included. The
+  /// global declarations of a program are only kept if it has no errors,
+  /// since it isn't run otherwise.
+  pub fn check(&mut self, program: &ast::Program) -> Vec<CheckError> {
+    self.previous_globals = self.globals.clone();
+    self.check_stmts(program);
+
+    let errors = std::mem::take(&mut self.errors);
+    if errors
+      .iter()
+      .any(|error| error.severity() == Severity::Error)
+    {
+      self.rollback();
+    }
+    for binding in self.globals.values_mut() {
+      binding.span = None;
+    }
+    errors
+  }
+
+  /// Forget the global declarations of the last checked program, because it
+  /// failed at runtime. Those it made before failing are forgotten as well,
+  /// so the evaluator is left to reject assigning to such a constant.
+  pub fn rollback(&mut self) {
+    self.globals = self.previous_globals.clone();
+  }
+}
+
+// scopes
+impl Checker {
+  fn check_in_scope(&mut self, bindings: Vec<(&str, Span)>, stmts: &ast::BlockStatement) {
+    self.scopes.push(Scope::new());
+    for (name, span) in bindings {
+      self.declare(name, BindingKind::Variable, span);
+    }
+    self.check_stmts(stmts);
+    self.scopes.pop();
+  }
+
+  fn declare(&mut self, name: &str, kind: BindingKind, span: Span) {
+    let scope = self.scopes.last_mut().unwrap_or(&mut self.globals);
+    let binding = Binding {
+      kind,
+      span: Some(span),
+    };
+    let previous = match scope.insert(name.to_owned(), binding) {
+      Some(previous) => previous,
+      None => return,
+    };
+
+    let constant = previous.kind == BindingKind::Const;
+    let severity = match self.redeclaration {
+      _ if constant => Severity::Error,
+      Redeclaration::Allow => return,
+      Redeclaration::Warn => Severity::Warning,
+      Redeclaration::Error => Severity::Error,
+    };
+    self.errors.push(CheckError::new(
+      CheckErrorKind::Redeclaration {
+        name: name.to_owned(),
+        previous: previous.span,
+        constant,
+      },
+      severity,
+      span,
+    ));
+  }
+
+  /// Find the nearest binding of `name`, walking outward like
+  /// `Environment::get` of the interpreter.
+  fn resolve(&self, name: &str) -> Option<Binding> {
+    self
+      .scopes
+      .iter()
+      .rev()
+      .chain(std::iter::once(&self.globals))
+      .find_map(|scope| scope.get(name).copied())
+  }
+}
+
+// statements
+impl Checker {
+  fn check_stmts(&mut self, stmts: &[Spanned<Statement>]) {
+    for stmt in stmts {
+      self.check_stmt(stmt);
+    }
+  }
+
+  fn check_stmt(&mut self, stmt: &Spanned<Statement>) {
+    match &stmt.node {
+      // the value can't refer to the binding it's assigned to
+      Statement::Let(name, value) => {
+        self.check_expr(value);
+        self.declare(&name.node.0, BindingKind::Variable, stmt.span);
+      }
+      Statement::Const(name, value) => {
+        self.check_expr(value);
+        self.declare(&name.node.0, BindingKind::Const, stmt.span);
+      }
+      Statement::Return(expr) | Statement::Expr(expr) => self.check_expr(expr),
+      Statement::Break | Statement::Continue | Statement::Error => {}
+    }
+  }
+}
+
+// expressions
+impl Checker {
+  fn check_expr(&mut self, expr: &Spanned<Expr>) {
+    match &expr.node {
+      Expr::Ident(_) | Expr::Literal(_) => {}
+      Expr::Prefix(_, operand) | Expr::Postfix(operand, _) => self.check_expr(operand),
+      Expr::Infix(left, _, right) => {
+        self.check_expr(left);
+        self.check_expr(right);
+      }
+      Expr::If {
+        condition,
+        consequence,
+        alternative,
+      } => {
+        self.check_expr(condition);
+        self.check_in_scope(vec![], consequence);
+        if let Some(alternative) = alternative {
+          self.check_in_scope(vec![], alternative);
+        }
+      }
+      Expr::While { condition, body } => {
+        self.check_expr(condition);
+        self.check_in_scope(vec![], body);
+      }
+      // loop variables are bound in the scope of the body
+      Expr::For {
+        binding,
+        iterable,
+        body,
+      } => {
+        self.check_expr(iterable);
+        let bindings = match binding {
+          ast::ForBinding::Item(name) => vec![(&*name.node.0, name.span)],
+          ast::ForBinding::Entry(key, value) => {
+            vec![(&*key.node.0, key.span), (&*value.node.0, value.span)]
+          }
+        };
+        self.check_in_scope(bindings, body);
+      }
+      // so are parameters
+      Expr::Function { params, body } => {
+        let bindings = params
+          .iter()
+          .map(|param| (&*param.node.0, param.span))
+          .collect();
+        self.check_in_scope(bindings, body);
+      }
+      Expr::Call {
+        function,
+        arguments,
+      } => {
+        self.check_expr(function);
+        arguments
+          .iter()
+          .for_each(|argument| self.check_expr(argument));
+      }
+      Expr::Array(elements) => elements.iter().for_each(|element| self.check_expr(element)),
+      Expr::Hash(pairs) => {
+        for (key, value) in pairs {
+          self.check_expr(key);
+          self.check_expr(value);
+        }
+      }
+      Expr::Index { left, index } => {
+        self.check_expr(left);
+        self.check_expr(index);
+      }
+      // the bindings of a pattern are scoped to its arm
+      Expr::Match { subject, arms } => {
+        self.check_expr(subject);
+        for arm in arms {
+          self.scopes.push(Scope::new());
+          self.declare_pattern(&arm.pattern);
+          if let Some(guard) = &arm.guard {
+            self.check_expr(guard);
+          }
+          self.check_stmts(&arm.body);
+          self.scopes.pop();
+        }
+        self.check_arms(subject.span, arms);
+      }
+      Expr::Assign { target, value, .. } => {
+        self.check_expr(target);
+        self.check_expr(value);
+        self.check_assign_target(target);
+      }
+    }
+  }
+
+  /// Assigning to an element of a constant rebinds it as well, since values
+  /// aren't shared.
+  fn check_assign_target(&mut self, target: &Spanned<Expr>) {
+    let mut root = &target.node;
+    while let Expr::Index { left, .. } = root {
+      root = &left.node;
+    }
+    let name = match root {
+      Expr::Ident(ast::Ident(name)) => &**name,
+      _ => return,
+    };
+
+    if let Some(Binding {
+      kind: BindingKind::Const,
+      span: declared,
+    }) = self.resolve(name)
+    {
+      self.errors.push(CheckError::new(
+        CheckErrorKind::AssignToConst {
+          name: name.to_owned(),
+          declared,
+        },
+        Severity::Error,
+        target.span,
+      ));
+    }
+  }
+}
+
+// patterns
+impl Checker {
+  fn declare_pattern(&mut self, pattern: &Spanned<Pattern>) {
+    match &pattern.node {
+      Pattern::Binding(ast::Ident(name)) => self.declare(name, BindingKind::Variable, pattern.span),
+      Pattern::Array { elements, rest } => {
+        for element in elements.iter().chain(rest.as_deref()) {
+          self.declare_pattern(element);
+        }
+      }
+      Pattern::Hash(pairs) => {
+        for (_, value) in pairs {
+          self.declare_pattern(value);
+        }
+      }
+      Pattern::Wildcard | Pattern::Literal(_) => {}
+    }
+  }
+
+  /// Values aren't typed, so a `match` only surely matches its subject if it
+  /// has an unguarded arm with an irrefutable pattern, or unguarded arms for
+  /// both `true` and `false` if it's a bool. An arm is unreachable if an
+  /// earlier unguarded arm matches everything it does.
+  fn check_arms(&mut self, subject: Span, arms: &[ast::MatchArm]) {
+    for (index, arm) in arms.iter().enumerate() {
+      let covering = arms[..index].iter().find(|earlier| {
+        earlier.guard.is_none() && covers(&earlier.pattern.node, &arm.pattern.node)
+      });
+      if let Some(earlier) = covering {
+        self.errors.push(CheckError::new(
+          CheckErrorKind::UnreachableArm {
+            covered_by: earlier.pattern.span,
+          },
+          Severity::Warning,
+          arm.pattern.span,
+        ));
+      }
+    }
+
+    let unguarded = || arms.iter().filter(|arm| arm.guard.is_none());
+    let matches_bool =
+      |val| unguarded().any(|arm| arm.pattern.node == Pattern::Literal(Literal::Bool(val)));
+    let exhaustive = unguarded().any(|arm| arm.pattern.node.is_irrefutable())
+      || (matches_bool(true) && matches_bool(false));
+    if !exhaustive {
+      self.errors.push(CheckError::new(
+        CheckErrorKind::NonExhaustiveMatch,
+        Severity::Warning,
+        subject,
+      ));
+    }
+  }
+}
+
+/// Whether every value `pattern` matches is matched by `by` as well.
+fn covers(by: &Pattern, pattern: &Pattern) -> bool {
+  match (by, pattern) {
+    (by, _) if by.is_irrefutable() => true,
+    (Pattern::Literal(by), Pattern::Literal(literal)) => same_literal(by, literal),
+    (
+      Pattern::Array {
+        elements: by_elements,
+        rest: by_rest,
+      },
+      Pattern::Array { elements, rest },
+    ) => {
+      let covers_len = match (by_rest, rest) {
+        (None, None) => by_elements.len() == elements.len(),
+        // `rest` matches arrays of any length
+        (None, Some(_)) => false,
+        (Some(_), _) => by_elements.len() <= elements.len(),
+      };
+      covers_len
+        && by_elements
+          .iter()
+          .zip(elements)
+          .all(|(by, element)| covers(&by.node, &element.node))
+      // the elements `by` leaves to its rest pattern, which is irrefutable
+    }
+    (Pattern::Hash(by_pairs), Pattern::Hash(pairs)) => by_pairs.iter().all(|(by_key, by)| {
+      pairs
+        .iter()
+        .any(|(key, value)| same_literal(&by_key.node, &key.node) && covers(&by.node, &value.node))
+    }),
+    _ => false,
+  }
+}
+
+/// Literals are compared like `==` compares their values, so `1` and `1.0` are
+/// the same.
+fn same_literal(left: &Literal, right: &Literal) -> bool {
+  match (left, right) {
+    (Literal::Int(int), Literal::Float(float)) | (Literal::Float(float), Literal::Int(int)) => {
+      *int as f64 == *float
+    }
+    (left, right) => left == right,
+  }
+}
@@ -0,0 +1,357 @@
+use super::{CheckErrorKind, Checker, Redeclaration};
+use crate::diagnostic::Severity;
+use crate::lexer::Lexer;
+use crate::parser::Parser;
+use crate::span::Span;
+
+fn check(checker: &mut Checker, input: &str) -> Vec<(CheckErrorKind, Severity, Span)> {
+  let mut parser = Parser::new(Lexer::new(input));
+  let parser = parser.parse();
+  assert!(parser.get_errors().is_empty(), "{}", input);
+
+  checker
+    .check(&parser.get_stmts())
+    .into_iter()
+    .map(|error| (error.kind().clone(), error.severity(), error.span()))
+    .collect()
+}
+
+#[test]
+fn assign_to_const() {
+  let cases = vec![
+    ("const a = 1; a = 2", Span::new(13, 14), Span::new(0, 12)),
+    ("const a = 1; a += 2", Span::new(13, 14), Span::new(0, 12)),
+    (
+      "const a = [1]; a[0] = 2",
+      Span::new(15, 19),
+      Span::new(0, 14),
+    ),
+    (
+      "const a = 1; let f = fn() { a = 2 }",
+      Span::new(28, 29),
+      Span::new(0, 12),
+    ),
+    (
+      "const a = 1; for (x in [1]) { if (x) { a = x } }",
+      Span::new(39, 40),
+      Span::new(0, 12),
+    ),
+  ];
+
+  for (input, span, declared) in cases {
+    assert_eq!(
+      check(&mut Checker::new(), input),
+      vec![(
+        CheckErrorKind::AssignToConst {
+          name: "a".to_string(),
+          declared: Some(declared),
+        },
+        Severity::Error,
+        span,
+      )],
+      "{}",
+      input
+    );
+  }
+}
+
+#[test]
+fn assign_to_shadowing_binding() {
+  let cases = vec![
+    "let a = 1; a = 2",
+    "const a = 1; if (true) { let a = 1; a = 2 }",
+    "const a = 1; let f = fn(a) { a = 2 }",
+    "const a = 1; for (a in [1]) { a = 2 }",
+    "const a = [1]; let b = a; b[0] = 2",
+  ];
+
+  for input in cases {
+    assert_eq!(check(&mut Checker::new(), input), vec![], "{}", input);
+  }
+}
+
+#[test]
+fn redeclaration_policies() {
+  let input = "let a = 1; const a = 2; if (true) { let a = 3; }";
+  let previous = Span::new(0, 10);
+  let span = Span::new(11, 23);
+
+  let cases = vec![
+    (Redeclaration::Allow, None),
+    (Redeclaration::Warn, Some(Severity::Warning)),
+    (Redeclaration::Error, Some(Severity::Error)),
+  ];
+
+  for (redeclaration, severity) in cases {
+    let mut checker = Checker::new().with_redeclaration(redeclaration);
+    let expected = severity
+      .map(|severity| {
+        (
+          CheckErrorKind::Redeclaration {
+            name: "a".to_string(),
+            previous: Some(previous),
+            constant: false,
+          },
+          severity,
+          span,
+        )
+      })
+      .into_iter()
+      .collect::<Vec<_>>();
+    assert_eq!(check(&mut checker, input), expected, "{:?}", redeclaration);
+  }
+}
+
+#[test]
+fn redeclare_constants() {
+  let input = "const a = 1; let a = 2; if (true) { let a = 3; }";
+
+  for redeclaration in [
+    Redeclaration::Allow,
+    Redeclaration::Warn,
+    Redeclaration::Error,
+  ] {
+    let mut checker = Checker::new().with_redeclaration(redeclaration);
+    assert_eq!(
+      check(&mut checker, input),
+      vec![(
+        CheckErrorKind::Redeclaration {
+          name: "a".to_string(),
+          previous: Some(Span::new(0, 12)),
+          constant: true,
+        },
+        Severity::Error,
+        Span::new(13, 23),
+      )],
+      "{:?}",
+      redeclaration
+    );
+  }
+}
+
+#[test]
+fn redeclare_bindings_of_a_scope() {
+  let cases = vec![
+    ("fn(a) { let a = 1; }", "a"),
+    ("fn(a, a) { a }", "a"),
+    ("for (k, v in {}) { let v = 1; }", "v"),
+    ("match (a) { [x] => { let x = 1; x }, _ => 0 }", "x"),
+  ];
+
+  for (input, name) in cases {
+    let mut checker = Checker::new().with_redeclaration(Redeclaration::Warn);
+    let errors = check(&mut checker, input);
+
+    assert_eq!(errors.len(), 1, "{}", input);
+    assert!(
+      matches!(&errors[0].0, CheckErrorKind::Redeclaration { name: found, .. } if found == name),
+      "{}",
+      input
+    );
+  }
+}
+
+#[test]
+fn render_redeclared_bindings() {
+  use crate::diagnostic::{Renderer, Source};
+
+  let cases = vec![
+    (
+      "fn(a, a) { a }",
+      Redeclaration::Warn,
+      "\
+warning[E0016]: `a` is already declared in this scope
+ --> repl:1:7
+  |
+1 | fn(a, a) { a }
+  |    -  ^ redeclared here
+  |    |
+  |    first declared here
+  |
+  = note: the new binding replaces the previous one
+",
+    ),
+    (
+      "for (k, k in h) {}",
+      Redeclaration::Error,
+      "\
+error[E0016]: `k` is already declared in this scope
+ --> repl:1:9
+  |
+1 | for (k, k in h) {}
+  |      -  ^ redeclared here
+  |      |
+  |      first declared here
+  |
+  = help: assign to the existing binding instead, or pick another name
+",
+    ),
+    (
+      "const a = 1; let a = 2",
+      Redeclaration::Allow,
+      "\
+error[E0016]: `a` is already declared in this scope
+ --> repl:1:14
+  |
+1 | const a = 1; let a = 2
+  | ------------ ^^^^^^^^^ redeclared here
+  | |
+  | first declared here
+  |
+  = help: pick another name
+  = note: a constant can't be redeclared in the scope it's declared in
+",
+    ),
+  ];
+
+  for (input, redeclaration, expected) in cases {
+    let mut parser = Parser::new(Lexer::new(input));
+    let stmts = parser.parse().get_stmts();
+    let errors = Checker::new()
+      .with_redeclaration(redeclaration)
+      .check(&stmts);
+
+    let source = Source::new("repl", input);
+    assert_eq!(
+      Renderer::plain().render(&errors[0].to_diagnostic(), &source),
+      expected
+    );
+  }
+}
+
+#[test]
+fn check_across_programs() {
+  let mut checker = Checker::new().with_redeclaration(Redeclaration::Warn);
+
+  assert_eq!(check(&mut checker, "const a = 1;"), vec![]);
+  // spans of earlier programs don't point into the current one
+  assert_eq!(
+    check(&mut checker, "a = 2"),
+    vec![(
+      CheckErrorKind::AssignToConst {
+        name: "a".to_string(),
+        declared: None,
+      },
+      Severity::Error,
+      Span::new(0, 1),
+    )]
+  );
+
+  // declarations of a program with errors are dropped, since it isn't run
+  assert_eq!(check(&mut checker, "const b = 1; a = 2").len(), 1);
+  assert_eq!(check(&mut checker, "b = 2"), vec![]);
+
+  // but those of a program with warnings aren't
+  assert_eq!(check(&mut checker, "let b = 1; const b = 2;").len(), 1);
+  assert_eq!(check(&mut checker, "b = 3").len(), 1);
+}
+
+#[test]
+fn match_arms() {
+  let unreachable = |covered_by, span| {
+    (
+      CheckErrorKind::UnreachableArm { covered_by },
+      Severity::Warning,
+      span,
+    )
+  };
+  let cases = vec![
+    (
+      "match (a) { [x, ..] => x, [1, 2] => 2 }",
+      vec![
+        unreachable(Span::new(12, 19), Span::new(26, 32)),
+        (
+          CheckErrorKind::NonExhaustiveMatch,
+          Severity::Warning,
+          Span::new(7, 8),
+        ),
+      ],
+    ),
+    (
+      "match (a) { 1 if a > 0 => 1, 1.0 => 2, 1 => 3, _ => 4 }",
+      vec![unreachable(Span::new(29, 32), Span::new(39, 40))],
+    ),
+    (
+      r#"match (a) { {"k": x} => x, {"j": 1, "k": 2} => 2, y => y }"#,
+      vec![unreachable(Span::new(12, 20), Span::new(27, 43))],
+    ),
+    ("match (a) { [x] => x, [x, ..] => x, _ => a }", vec![]),
+    ("match (a) { x if x > 0 => x, x => -x }", vec![]),
+    ("match (a) { true => 1, false => { 0 } }", vec![]),
+    (
+      "match (a) { true => 1, false if a => 0 }",
+      vec![(
+        CheckErrorKind::NonExhaustiveMatch,
+        Severity::Warning,
+        Span::new(7, 8),
+      )],
+    ),
+  ];
+
+  for (input, expected) in cases {
+    assert_eq!(check(&mut Checker::new(), input), expected, "{}", input);
+  }
+}
+
+#[test]
+fn match_arm_bindings() {
+  let mut checker = Checker::new().with_redeclaration(Redeclaration::Warn);
+  let errors = check(
+    &mut checker,
+    "let x = 1; match (x) { [x, ..x] => x, x => x }",
+  );
+
+  assert_eq!(
+    errors,
+    vec![(
+      CheckErrorKind::Redeclaration {
+        name: "x".to_string(),
+        previous: Some(Span::new(24, 25)),
+        constant: false,
+      },
+      Severity::Warning,
+      Span::new(27, 30),
+    )]
+  );
+}
+
+#[test]
+fn error_messages() {
+  let cases = vec![
+    (
+      Redeclaration::Allow,
+      "const a = 1; a = 2",
+      "error[E0015]: cannot assign to constant `a`",
+    ),
+    (
+      Redeclaration::Warn,
+      "let a = 1; let a = 2;",
+      "warning[E0016]: `a` is already declared in this scope",
+    ),
+    (
+      Redeclaration::Error,
+      "let a = 1; let a = 2;",
+      "error[E0016]: `a` is already declared in this scope",
+    ),
+    (
+      Redeclaration::Allow,
+      "match (a) { 1 => 1 }",
+      "warning[E0017]: non-exhaustive match",
+    ),
+    (
+      Redeclaration::Allow,
+      "match (a) { _ => 1, 1 => 2 }",
+      "warning[E0018]: unreachable match arm",
+    ),
+  ];
+
+  for (redeclaration, input, expected) in cases {
+    let mut parser = Parser::new(Lexer::new(input));
+    let stmts = parser.parse().get_stmts();
+    let errors = Checker::new()
+      .with_redeclaration(redeclaration)
+      .check(&stmts);
+
+    assert_eq!(errors[0].to_string(), expected, "{}", input);
+  }
+}
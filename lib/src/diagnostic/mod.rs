@@ -0,0 +1,432 @@
+#[cfg(test)]
+mod test;
+
+use crate::span::{LineIndex, Span};
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+  Error,
+  Warning,
+}
+
+impl fmt::Display for Severity {
+  fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+    match *self {
+      Severity::Error => write!(formatter, "error"),
+      Severity::Warning => write!(formatter, "warning"),
+    }
+  }
+}
+
+/// The primary label points at the cause of a diagnostic, secondary labels
+/// add context around it, e.g. where an unclosed delimiter was opened.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LabelStyle {
+  Primary,
+  Secondary,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Label {
+  pub style: LabelStyle,
+  pub span: Span,
+  pub message: String,
+}
+
+impl Label {
+  pub fn primary(span: Span, message: impl Into<String>) -> Self {
+    Label {
+      style: LabelStyle::Primary,
+      span,
+      message: message.into(),
+    }
+  }
+
+  pub fn secondary(span: Span, message: impl Into<String>) -> Self {
+    Label {
+      style: LabelStyle::Secondary,
+      span,
+      message: message.into(),
+    }
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NoteKind {
+  Note,
+  Help,
+}
+
+impl fmt::Display for NoteKind {
+  fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+    match *self {
+      NoteKind::Note => write!(formatter, "note"),
+      NoteKind::Help => write!(formatter, "help"),
+    }
+  }
+}
+
+/// A message about a source text, which is rendered along with the source
+/// lines it points at.
+///
+/// ```text
+/// error[E0002]: expected `)`, found `;`
+///  --> repl:1:15
+///   |
+/// 1 | let x = (1 + 2;
+///   |         -     ^ expected `)`
+///   |         |
+///   |         unclosed delimiter
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+  pub severity: Severity,
+  pub code: Option<String>,
+  pub message: String,
+  pub labels: Vec<Label>,
+  pub notes: Vec<(NoteKind, String)>,
+}
+
+impl Diagnostic {
+  pub fn new(severity: Severity, message: impl Into<String>) -> Self {
+    Diagnostic {
+      severity,
+      code: None,
+      message: message.into(),
+      labels: vec![],
+      notes: vec![],
+    }
+  }
+
+  pub fn error(message: impl Into<String>) -> Self {
+    Diagnostic::new(Severity::Error, message)
+  }
+
+  pub fn warning(message: impl Into<String>) -> Self {
+    Diagnostic::new(Severity::Warning, message)
+  }
+
+  pub fn with_code(mut self, code: impl Into<String>) -> Self {
+    self.code = Some(code.into());
+    self
+  }
+
+  pub fn with_label(mut self, label: Label) -> Self {
+    self.labels.push(label);
+    self
+  }
+
+  pub fn with_note(mut self, note: impl Into<String>) -> Self {
+    self.notes.push((NoteKind::Note, note.into()));
+    self
+  }
+
+  pub fn with_help(mut self, help: impl Into<String>) -> Self {
+    self.notes.push((NoteKind::Help, help.into()));
+    self
+  }
+
+  /// The span of the first primary label, which is where the diagnostic is
+  /// reported.
+  pub fn primary_span(&self) -> Option<Span> {
+    self
+      .labels
+      .iter()
+      .find(|label| label.style == LabelStyle::Primary)
+      .map(|label| label.span)
+  }
+}
+
+/// A named source text which diagnostics are rendered against.
+pub struct Source<'source> {
+  name: &'source str,
+  text: &'source str,
+  index: LineIndex<'source>,
+}
+
+impl<'source> Source<'source> {
+  pub fn new(name: &'source str, text: &'source str) -> Self {
+    Source {
+      name,
+      text,
+      index: LineIndex::new(text),
+    }
+  }
+
+  pub fn name(&self) -> &'source str {
+    self.name
+  }
+
+  pub fn text(&self) -> &'source str {
+    self.text
+  }
+
+  pub fn index(&self) -> &LineIndex<'source> {
+    &self.index
+  }
+}
+
+mod style {
+  pub const RESET: &str = "\x1b[0m";
+  pub const BOLD: &str = "\x1b[1m";
+  pub const RED: &str = "\x1b[1;31m";
+  pub const YELLOW: &str = "\x1b[1;33m";
+  pub const BLUE: &str = "\x1b[1;34m";
+}
+
+/// Render diagnostics rustc-style, either as plain text or with ANSI colors for
+/// terminals.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Renderer {
+  colored: bool,
+}
+
+/// A label resolved to the line it starts on, with 0-based character columns.
+struct LineLabel<'label> {
+  label: &'label Label,
+  start: usize,
+  end: usize,
+}
+
+impl Renderer {
+  pub fn plain() -> Self {
+    Renderer { colored: false }
+  }
+
+  pub fn colored() -> Self {
+    Renderer { colored: true }
+  }
+
+  pub fn render(&self, diagnostic: &Diagnostic, source: &Source) -> String {
+    let mut out = String::new();
+    let severity_style = match diagnostic.severity {
+      Severity::Error => style::RED,
+      Severity::Warning => style::YELLOW,
+    };
+
+    // header: `error[E0001]: message`
+    let header = match &diagnostic.code {
+      Some(code) => format!("{}[{}]", diagnostic.severity, code),
+      None => diagnostic.severity.to_string(),
+    };
+    out.push_str(&self.paint(severity_style, &header));
+    out.push_str(&self.paint(style::BOLD, &format!(": {}", diagnostic.message)));
+    out.push('\n');
+
+    let index = source.index();
+    let mut labels = diagnostic
+      .labels
+      .iter()
+      .map(|label| (index.position(label.span.start), label))
+      .collect::<Vec<_>>();
+    labels.sort_by_key(|(position, _)| (position.line, position.column));
+
+    let max_line = labels
+      .iter()
+      .map(|(position, _)| position.line)
+      .max()
+      .unwrap_or(1);
+    let gutter_width = max_line.to_string().len();
+    let gutter = " ".repeat(gutter_width);
+    let pipe = self.paint(style::BLUE, "|");
+
+    // location: ` --> name:line:column`
+    let location = diagnostic
+      .primary_span()
+      .or_else(|| labels.first().map(|(_, label)| label.span))
+      .map(|span| index.position(span.start));
+    match location {
+      Some(position) => out.push_str(&format!(
+        "{}{} {}:{}\n",
+        gutter,
+        self.paint(style::BLUE, "-->"),
+        source.name(),
+        position
+      )),
+      None => out.push_str(&format!(
+        "{}{} {}\n",
+        gutter,
+        self.paint(style::BLUE, "-->"),
+        source.name()
+      )),
+    }
+
+    if !labels.is_empty() {
+      out.push_str(&format!("{} {}\n", gutter, pipe));
+    }
+
+    let mut previous_line: Option<usize> = None;
+    let mut cursor = 0;
+    while cursor < labels.len() {
+      let line = labels[cursor].0.line;
+      let mut line_labels = vec![];
+      while cursor < labels.len() && labels[cursor].0.line == line {
+        let (position, label) = labels[cursor];
+        line_labels.push(self.resolve_label(source, line, position.column - 1, label));
+        cursor += 1;
+      }
+
+      // a single line gap is cheaper to show than to elide
+      match previous_line {
+        Some(previous_line) if line == previous_line + 2 => {
+          self.render_source_line(&mut out, source, previous_line + 1, gutter_width, &pipe)
+        }
+        Some(previous_line) if line > previous_line + 2 => {
+          out.push_str(&format!("{}\n", self.paint(style::BLUE, "...")))
+        }
+        _ => {}
+      }
+      previous_line = Some(line);
+
+      self.render_source_line(&mut out, source, line, gutter_width, &pipe);
+      self.render_line_labels(&mut out, &gutter, &pipe, severity_style, &line_labels);
+    }
+
+    if !diagnostic.notes.is_empty() && !labels.is_empty() {
+      out.push_str(&format!("{} {}\n", gutter, pipe));
+    }
+    for (kind, note) in &diagnostic.notes {
+      out.push_str(&format!(
+        "{} {} {}: {}\n",
+        gutter,
+        self.paint(style::BLUE, "="),
+        self.paint(style::BOLD, &kind.to_string()),
+        note
+      ));
+    }
+
+    out
+  }
+
+  fn render_source_line(
+    &self,
+    out: &mut String,
+    source: &Source,
+    line: usize,
+    gutter_width: usize,
+    pipe: &str,
+  ) {
+    out.push_str(&format!(
+      "{} {} {}\n",
+      self.paint(
+        style::BLUE,
+        &format!("{:>width$}", line, width = gutter_width)
+      ),
+      pipe,
+      source.index().line(line).unwrap_or_default()
+    ));
+  }
+
+  fn resolve_label<'label>(
+    &self,
+    source: &Source,
+    line: usize,
+    start: usize,
+    label: &'label Label,
+  ) -> LineLabel<'label> {
+    // a span covering several lines is underlined up to the end of its first line
+    let text = source.index().line(line).unwrap_or_default();
+    let line_end = start + text.chars().skip(start).count();
+    let width = source
+      .text()
+      .get(label.span.start..label.span.end)
+      .map_or(0, |text| text.chars().take_while(|ch| *ch != '\n').count());
+    LineLabel {
+      label,
+      start,
+      // empty spans, e.g. the end of input, are still marked with one character
+      end: (start + width).min(line_end).max(start + 1),
+    }
+  }
+
+  /// Underline every label of a line, then hang their messages below from
+  /// right to left. The rightmost message goes right after the underlines
+  /// when nothing is in its way.
+  ///
+  /// ```text
+  ///   |         -     ^ expected `)`
+  ///   |         |
+  ///   |         unclosed delimiter
+  /// ```
+  fn render_line_labels(
+    &self,
+    out: &mut String,
+    gutter: &str,
+    pipe: &str,
+    severity_style: &'static str,
+    labels: &[LineLabel],
+  ) {
+    let marker_style = |label: &Label| match label.style {
+      LabelStyle::Primary => severity_style,
+      LabelStyle::Secondary => style::BLUE,
+    };
+
+    let mut underline = String::new();
+    let mut column = 0;
+    for line_label in labels {
+      if line_label.end <= column {
+        continue;
+      }
+      let start = line_label.start.max(column);
+      let marker = match line_label.label.style {
+        LabelStyle::Primary => "^",
+        LabelStyle::Secondary => "-",
+      };
+      underline.push_str(&" ".repeat(start - column));
+      underline.push_str(&self.paint(
+        marker_style(line_label.label),
+        &marker.repeat(line_label.end - start),
+      ));
+      column = line_label.end;
+    }
+
+    let hanging = labels
+      .iter()
+      .filter(|line_label| !line_label.label.message.is_empty())
+      .collect::<Vec<_>>();
+    let mut hanging = &hanging[..];
+    if let Some((last, rest)) = hanging.split_last() {
+      if rest.iter().all(|line_label| line_label.end <= last.start) {
+        underline.push(' ');
+        underline.push_str(&self.paint(marker_style(last.label), &last.label.message));
+        hanging = rest;
+      }
+    }
+    out.push_str(&format!("{} {} {}\n", gutter, pipe, underline));
+
+    // `|` connectors for the labels on the left of the one being hung, labels
+    // starting at the same column share one and their messages are stacked
+    let connectors = |until: usize, before: usize| {
+      let mut row = String::new();
+      let mut column = 0;
+      for line_label in &hanging[..until] {
+        if line_label.start < column || line_label.start >= before {
+          continue;
+        }
+        row.push_str(&" ".repeat(line_label.start - column));
+        row.push_str(&self.paint(marker_style(line_label.label), "|"));
+        column = line_label.start + 1;
+      }
+      (row, column)
+    };
+    for current in (0..hanging.len()).rev() {
+      let line_label = hanging[current];
+      let (row, _) = connectors(current + 1, usize::MAX);
+      out.push_str(&format!("{} {} {}\n", gutter, pipe, row));
+
+      let (mut row, column) = connectors(current, line_label.start);
+      row.push_str(&" ".repeat(line_label.start - column));
+      row.push_str(&self.paint(marker_style(line_label.label), &line_label.label.message));
+      out.push_str(&format!("{} {} {}\n", gutter, pipe, row));
+    }
+  }
+
+  fn paint(&self, style: &str, text: &str) -> String {
+    if self.colored {
+      format!("{}{}{}", style, text, style::RESET)
+    } else {
+      text.to_owned()
+    }
+  }
+}
@@ -0,0 +1,131 @@
+use super::{Diagnostic, Label, Renderer, Source};
+use crate::span::Span;
+
+#[test]
+fn render_primary_label() {
+  let source = Source::new("main.mk", "let a = 1;\nlet b = a +;\n");
+  let diagnostic = Diagnostic::error("Unexpected Token")
+    .with_code("E0003")
+    .with_label(Label::primary(Span::new(22, 23), "expected an expression"));
+
+  assert_eq!(
+    Renderer::plain().render(&diagnostic, &source),
+    "\
+error[E0003]: Unexpected Token
+ --> main.mk:2:12
+  |
+2 | let b = a +;
+  |            ^ expected an expression
+"
+  );
+}
+
+#[test]
+fn render_secondary_labels_and_notes() {
+  let source = Source::new("repl", "let x = (1 + 2;");
+  let diagnostic = Diagnostic::error("expected `)`, found `;`")
+    .with_label(Label::primary(Span::new(14, 15), "expected `)`"))
+    .with_label(Label::secondary(Span::new(8, 9), "unclosed delimiter"))
+    .with_help("close the delimiter with `)`")
+    .with_note("delimiters must be balanced");
+
+  assert_eq!(
+    Renderer::plain().render(&diagnostic, &source),
+    "\
+error: expected `)`, found `;`
+ --> repl:1:15
+  |
+1 | let x = (1 + 2;
+  |         -     ^ expected `)`
+  |         |
+  |         unclosed delimiter
+  |
+  = help: close the delimiter with `)`
+  = note: delimiters must be balanced
+"
+  );
+}
+
+#[test]
+fn render_labels_on_the_same_span() {
+  let source = Source::new("repl", "let f = fn(a, a) { a }");
+  let diagnostic = Diagnostic::warning("`a` is already declared in this scope")
+    .with_label(Label::secondary(Span::new(0, 3), "in this binding"))
+    .with_label(Label::primary(Span::new(14, 15), "redeclared here"))
+    .with_label(Label::secondary(Span::new(14, 15), "shadows this"));
+
+  assert_eq!(
+    Renderer::plain().render(&diagnostic, &source),
+    "\
+warning: `a` is already declared in this scope
+ --> repl:1:15
+  |
+1 | let f = fn(a, a) { a }
+  | ---           ^
+  | |             |
+  | |             shadows this
+  | |             |
+  | |             redeclared here
+  | |
+  | in this binding
+"
+  );
+}
+
+#[test]
+fn render_labels_across_lines() {
+  let source = Source::new(
+    "main.mk",
+    "let f = fn(x) {\n  x\n\n\n\n\n\n\n\n  + true\n};",
+  );
+  let diagnostic = Diagnostic::warning("suspicious addition")
+    .with_label(Label::secondary(Span::new(0, 3), ""))
+    .with_label(Label::primary(Span::new(31, 35), "this is a boolean"));
+
+  assert_eq!(
+    Renderer::plain().render(&diagnostic, &source),
+    "\
+warning: suspicious addition
+  --> main.mk:10:5
+   |
+ 1 | let f = fn(x) {
+   | ---
+...
+10 |   + true
+   |     ^^^^ this is a boolean
+"
+  );
+}
+
+#[test]
+fn render_multiline_and_empty_spans() {
+  let source = Source::new("main.mk", "if (x) {\n  1\n");
+  let diagnostic = Diagnostic::error("unterminated block")
+    .with_label(Label::primary(Span::new(13, 13), "expected `}`"))
+    .with_label(Label::secondary(Span::new(7, 13), "block starts here"));
+
+  assert_eq!(
+    Renderer::plain().render(&diagnostic, &source),
+    "\
+error: unterminated block
+ --> main.mk:3:1
+  |
+1 | if (x) {
+  |        - block starts here
+2 |   1
+3 | 
+  | ^ expected `}`
+"
+  );
+}
+
+#[test]
+fn render_colored() {
+  let source = Source::new("repl", "1 +");
+  let diagnostic =
+    Diagnostic::error("Unexpected Token").with_label(Label::primary(Span::new(3, 3), "here"));
+  let rendered = Renderer::colored().render(&diagnostic, &source);
+
+  assert!(rendered.starts_with("\x1b[1;31merror\x1b[0m\x1b[1m: Unexpected Token\x1b[0m\n"));
+  assert!(rendered.contains("\x1b[1;31m^\x1b[0m \x1b[1;31mhere\x1b[0m"));
+}
@@ -1,64 +1,74 @@
 #[cfg(test)]
 mod test;
 
+use crate::span::{Span, Spanned};
 use crate::token;
-use crate::token::Token;
+use crate::token::{LexError, Token, TokenWithTrivia, Trivia, TriviaKind};
+use std::num::IntErrorKind;
 
 /// As known as scanner.
 /// 1. It could transform text literals into tokens.
 /// 2. It should only be manipulated by parser
+#[derive(Clone)]
 pub struct Lexer<'input> {
   input: &'input str,
-  bytes: Vec<u8>,
   /// current position in input (points to current char)
   pos: usize,
   /// current reading position in input (after current char)
   read_pos: usize,
   /// current char under examination
   ///
-  /// The low-level representation of Rust's String type is `Vec<u8>`,
-  /// see https://doc.rust-lang.org/book/ch08-02-strings.html#internal-representation
-  ch: u8,
+  /// Positions are byte offsets, while a char may be several bytes long in
+  /// UTF-8, see https://doc.rust-lang.org/book/ch08-02-strings.html#internal-representation
+  ch: char,
+  /// Whether comments are kept, see `Lexer::with_trivia`.
+  retain_trivia: bool,
+  /// Comments read since the last token.
+  trivia: Vec<Trivia<'input>>,
 }
 
 impl<'input> Lexer<'input> {
-  pub fn new(input: &str) -> Lexer {
+  pub fn new(input: &str) -> Lexer<'_> {
     let mut lexer = Lexer {
       input,
-      bytes: input.as_bytes().to_owned(),
       pos: 0,
       read_pos: 0,
-      ch: token::CHAR_NUL_BYTE,
+      ch: token::CHAR_NUL,
+      retain_trivia: false,
+      trivia: vec![],
     };
     lexer.read_char();
     lexer
   }
 
+  /// Keep comments instead of discarding them, they're returned by
+  /// `Lexer::read_with_trivia` along with the token following them.
+  pub fn with_trivia(mut self) -> Self {
+    self.retain_trivia = true;
+    self
+  }
+
   /// similar to `self.read_char`, except that it doesn't increment `self.pos`
   /// and `self.read_pos`. We only want to "peek" ahead in the input and not
   /// move around it.
-  fn peek_char(&mut self) -> u8 {
-    if self.read_pos >= self.input.len() {
-      token::CHAR_NUL_BYTE
-    } else {
-      self.bytes[self.read_pos]
-    }
+  fn peek_char(&self) -> char {
+    self
+      .input
+      .get(self.read_pos..)
+      .and_then(|rest| rest.chars().next())
+      .unwrap_or(token::CHAR_NUL)
   }
 
   fn read_char(&mut self) {
-    self.ch = if self.read_pos >= self.input.len() {
-      token::CHAR_NUL_BYTE
-    } else {
-      self.bytes[self.read_pos]
-    };
+    self.ch = self.peek_char();
     self.pos = self.read_pos;
-    self.read_pos += 1;
+    self.read_pos += self.ch.len_utf8();
   }
 
   fn read_identifier(&mut self) -> Token<'input> {
     let from = self.pos;
 
-    while let b'a'..=b'z' | b'A'..=b'Z' | b'_' = self.ch {
+    while let 'a'..='z' | 'A'..='Z' | '_' = self.ch {
       self.read_char();
     }
 
@@ -67,83 +77,381 @@ impl<'input> Lexer<'input> {
     match literal {
       "fn" => Token::Function,
       "let" => Token::Let,
+      "const" => Token::Const,
       "true" => Token::Bool(true),
       "false" => Token::Bool(false),
       "if" => Token::If,
       "else" => Token::Else,
+      "while" => Token::While,
+      "break" => Token::Break,
+      "continue" => Token::Continue,
+      "for" => Token::For,
+      "in" => Token::In,
+      "match" => Token::Match,
       "return" => Token::Return,
       _ => Token::Ident(literal),
     }
   }
 
+  /// Read an integer, or a float if it has a fraction or an exponent, e.g.
+  /// `1.5`, `2e10` or `2.5E-3`. Both sides of the decimal point must have
+  /// digits, `.5` and `1.` aren't numbers, so that `.` stays free for other
+  /// operators like `1..2`. Digits may be separated by `_`, e.g. `1_000`.
   fn read_number(&mut self) -> Token<'input> {
+    let radix = match (self.ch, self.peek_char()) {
+      ('0', 'x') => 16,
+      ('0', 'o') => 8,
+      ('0', 'b') => 2,
+      _ => 10,
+    };
+    if radix != 10 {
+      return self.read_prefixed_integer(radix);
+    }
+
     let from = self.pos;
+    let mut is_float = false;
+
+    self.read_digits();
+    if self.ch == '.' && self.peek_char().is_ascii_digit() {
+      is_float = true;
+      self.read_char();
+      self.read_digits();
+    }
+    if let 'e' | 'E' = self.ch {
+      is_float = true;
+      self.read_char();
+      if let '+' | '-' = self.ch {
+        self.read_char();
+      }
+      if !self.ch.is_ascii_digit() {
+        // swallow the rest of a malformed literal like `1e_` or `1ex`
+        while self.ch.is_ascii_alphanumeric() || self.ch == '_' {
+          self.read_char();
+        }
+        let literal = &self.input[from..self.pos];
+        return Token::Illegal(LexError::InvalidFloat(literal.to_owned()));
+      }
+      self.read_digits();
+    }
+
+    let literal = &self.input[from..self.pos];
+    let digits = literal.replace('_', "");
+    if is_float {
+      match digits.parse::<f64>() {
+        Ok(value) if value.is_finite() => Token::Float(value),
+        _ => Token::Illegal(LexError::InvalidFloat(literal.to_owned())),
+      }
+    } else {
+      Self::parse_integer(literal, &digits, 10)
+    }
+  }
 
-    while let b'0'..=b'9' = self.ch {
+  /// Read a `0x`, `0o` or `0b` integer literal. Any alphanumeric chars
+  /// following the prefix are part of the literal, so that e.g. `0b12` is
+  /// reported as a whole.
+  fn read_prefixed_integer(&mut self, radix: u32) -> Token<'input> {
+    let from = self.pos;
+    // skip the prefix
+    self.read_char();
+    self.read_char();
+    while self.ch.is_ascii_alphanumeric() || self.ch == '_' {
       self.read_char();
     }
-    match self.input[from..self.pos].parse::<i64>() {
+    let literal = &self.input[from..self.pos];
+    Self::parse_integer(literal, &literal[2..].replace('_', ""), radix)
+  }
+
+  /// Parse the `digits` of an integer `literal`. With the `bigint` feature,
+  /// literals which don't fit into an `i64` become a `Token::BigInt`.
+  fn parse_integer(literal: &str, digits: &str, radix: u32) -> Token<'input> {
+    match i64::from_str_radix(digits, radix) {
       Ok(value) => Token::Int(value),
-      _ => Token::Illegal,
+      #[cfg(feature = "bigint")]
+      Err(error) if *error.kind() == IntErrorKind::PosOverflow => {
+        match num_bigint::BigInt::parse_bytes(digits.as_bytes(), radix) {
+          Some(value) => Token::BigInt(value),
+          None => Token::Illegal(LexError::InvalidInteger(literal.to_owned())),
+        }
+      }
+      #[cfg(not(feature = "bigint"))]
+      Err(error) if *error.kind() == IntErrorKind::PosOverflow => {
+        Token::Illegal(LexError::IntegerOverflow(literal.to_owned()))
+      }
+      Err(_) => Token::Illegal(LexError::InvalidInteger(literal.to_owned())),
+    }
+  }
+
+  fn read_digits(&mut self) {
+    while let '0'..='9' | '_' = self.ch {
+      self.read_char();
+    }
+  }
+
+  /// Read a double-quoted string literal, resolving its escape sequences. It
+  /// stops at the closing quote, which is consumed by `read_token`.
+  fn read_string(&mut self) -> Token<'input> {
+    let mut literal = String::new();
+    let mut invalid_escape = None;
+
+    loop {
+      self.read_char();
+      if self.pos >= self.input.len() {
+        return Token::Illegal(LexError::UnterminatedString);
+      }
+      match self.ch {
+        '"' => break,
+        '\\' => match self.read_escape() {
+          Ok(ch) => literal.push(ch),
+          // keep going until the closing quote, so that the rest of the
+          // literal isn't lexed as code
+          Err(escape) => {
+            invalid_escape.get_or_insert(escape);
+          }
+        },
+        ch => literal.push(ch),
+      }
+    }
+
+    if let Some(escape) = invalid_escape {
+      return Token::Illegal(LexError::InvalidEscape(escape));
+    }
+    Token::Str(literal)
+  }
+
+  /// Read the escape sequence starting at the current `\`. On failure, the
+  /// offending sequence is returned as it's written in the source.
+  fn read_escape(&mut self) -> Result<char, String> {
+    let from = self.pos;
+    if self.read_pos >= self.input.len() {
+      return Err("\\".to_string());
+    }
+    self.read_char();
+    match self.ch {
+      'n' => Ok('\n'),
+      't' => Ok('\t'),
+      'r' => Ok('\r'),
+      '0' => Ok('\0'),
+      '"' => Ok('"'),
+      '\\' => Ok('\\'),
+      'u' => self.read_unicode_escape(from),
+      _ => Err(self.input[from..self.read_pos].to_string()),
+    }
+  }
+
+  /// Read the `{...}` part of a `\u{...}` escape, which holds 1 to 6 hex
+  /// digits of a Unicode scalar value.
+  fn read_unicode_escape(&mut self, from: usize) -> Result<char, String> {
+    if self.peek_char() != '{' {
+      return Err(self.input[from..self.read_pos].to_string());
+    }
+    self.read_char();
+
+    let digits_from = self.read_pos;
+    while self.peek_char().is_ascii_hexdigit() {
+      self.read_char();
+    }
+    let digits = &self.input[digits_from..self.read_pos];
+    if self.peek_char() != '}' {
+      return Err(self.input[from..self.read_pos].to_string());
+    }
+    self.read_char();
+
+    match digits.len() {
+      1..=6 => u32::from_str_radix(digits, 16)
+        .ok()
+        .and_then(char::from_u32)
+        .ok_or_else(|| self.input[from..self.read_pos].to_string()),
+      _ => Err(self.input[from..self.read_pos].to_string()),
+    }
+  }
+
+  /// Read the next token along with its span. Once the input is exhausted, it
+  /// keeps returning `Token::Eof` with an empty span at the end of the input.
+  pub fn move_to_next_tok(&mut self) -> Spanned<Token<'input>> {
+    self.read_with_trivia().token
+  }
+
+  /// Like `Lexer::move_to_next_tok`, along with the comments in front of the
+  /// token if trivia is retained. Comments at the end of the input come with
+  /// `Token::Eof`.
+  pub fn read_with_trivia(&mut self) -> TokenWithTrivia<'input> {
+    let token = match self.skip_trivia() {
+      Ok(()) => {
+        let start = self.pos.min(self.input.len());
+        let tok = self.read_token();
+        let end = self.pos.min(self.input.len());
+        Spanned::new(tok, Span::new(start, end))
+      }
+      Err(start) => Spanned::new(
+        Token::Illegal(LexError::UnterminatedComment),
+        Span::new(start, self.input.len()),
+      ),
+    };
+    TokenWithTrivia {
+      leading_trivia: std::mem::take(&mut self.trivia),
+      token,
     }
   }
 
-  pub fn move_to_next_tok(&mut self) -> token::Token<'input> {
-    self.skip_whitespace();
+  fn read_token(&mut self) -> Token<'input> {
     let tok = match self.ch {
       // operators
-      b'=' => {
-        if self.peek_char() == b'=' {
-          self.read_char();
-          Token::Equal
-        } else {
-          Token::Assign
-        }
+      '=' if self.peek_char() == '>' => {
+        self.read_char();
+        Token::FatArrow
       }
-      b'+' => Token::Plus,
-      b'-' => Token::Minus,
-      b'!' => {
-        if self.peek_char() == b'=' {
-          self.read_char();
-          Token::NotEqual
-        } else {
-          Token::Bang
-        }
+      '=' => self.read_with_equal(Token::Equal, Token::Assign),
+      '+' => self.read_with_equal(Token::PlusAssign, Token::Plus),
+      '-' => self.read_with_equal(Token::MinusAssign, Token::Minus),
+      '!' => self.read_with_equal(Token::NotEqual, Token::Bang),
+      '/' | '÷' => self.read_with_equal(Token::SlashAssign, Token::Slash),
+      '*' if self.peek_char() == '*' => {
+        self.read_char();
+        self.read_with_equal(Token::PowerAssign, Token::Power)
+      }
+      '*' | '×' => self.read_with_equal(Token::AsteriskAssign, Token::Asterisk),
+      '<' if self.peek_char() == '<' => {
+        self.read_char();
+        self.read_with_equal(Token::ShiftLeftAssign, Token::ShiftLeft)
+      }
+      '<' => self.read_with_equal(Token::LessThanEqual, Token::LessThan),
+      '>' if self.peek_char() == '>' => {
+        self.read_char();
+        self.read_with_equal(Token::ShiftRightAssign, Token::ShiftRight)
+      }
+      '>' => self.read_with_equal(Token::GreaterThanEqual, Token::GreaterThan),
+      '≠' => Token::NotEqual,
+      '≤' => Token::LessThanEqual,
+      '≥' => Token::GreaterThanEqual,
+      '%' => self.read_with_equal(Token::PercentAssign, Token::Percent),
+      '&' if self.peek_char() == '&' => self.read_doubled(Token::And, Token::Ampersand),
+      '&' => self.read_with_equal(Token::AmpersandAssign, Token::Ampersand),
+      '|' if self.peek_char() == '|' => self.read_doubled(Token::Or, Token::Pipe),
+      '|' => self.read_with_equal(Token::PipeAssign, Token::Pipe),
+      '^' => self.read_with_equal(Token::CaretAssign, Token::Caret),
+      '~' => Token::Tilde,
+      '?' => Token::Question,
+      // a lone `.` is left alone, `1.` isn't a float either
+      '.' if self.peek_char() == '.' => {
+        self.read_char();
+        self.read_with_equal(Token::DotDotEqual, Token::DotDot)
       }
-      b'/' => Token::Slash,
-      b'*' => Token::Asterisk,
-      b'<' => Token::LessThan,
-      b'>' => Token::GreaterThan,
 
       // delimiters
-      b';' => Token::Semicolon,
-      b',' => Token::Comma,
-      b'(' => Token::LParen,
-      b')' => Token::RParen,
-      b'{' => Token::LBrace,
-      b'}' => Token::RBrace,
-
-      b'a'..=b'z' | b'A'..=b'Z' | b'_' => {
+      ';' => Token::Semicolon,
+      ':' => Token::Colon,
+      ',' => Token::Comma,
+      '(' => Token::LParen,
+      ')' => Token::RParen,
+      '{' => Token::LBrace,
+      '}' => Token::RBrace,
+      '[' => Token::LBracket,
+      ']' => Token::RBracket,
+
+      '"' => self.read_string(),
+
+      'a'..='z' | 'A'..='Z' | '_' => {
         // NOTE: terminate next_token function evaluation and
         // use read_identifier's returned value as next_token returned value
         return self.read_identifier();
       }
-      b'0'..=b'9' => {
+      '0'..='9' => {
         // NOTE: terminate next_token function evaluation and
         // use read_identifier's returned value as next_token returned value
         return self.read_number();
       }
 
-      token::CHAR_NUL_BYTE => Token::Eof,
-      _ => Token::Illegal,
+      token::CHAR_NUL => Token::Eof,
+      ch => Token::Illegal(LexError::IllegalCharacter(ch)),
     };
     self.read_char();
     tok
   }
 
-  fn skip_whitespace(&mut self) {
-    while let b' ' | b'\t' | b'\n' | b'\r' = self.ch {
+  /// Read `with_equal` if the current char is followed by `=`, e.g. `<=`, or
+  /// `alone` otherwise.
+  fn read_with_equal(&mut self, with_equal: Token<'input>, alone: Token<'input>) -> Token<'input> {
+    if self.peek_char() == '=' {
+      self.read_char();
+      with_equal
+    } else {
+      alone
+    }
+  }
+
+  /// Read `doubled` if the current char is repeated, e.g. `&&`, or `alone`
+  /// otherwise.
+  fn read_doubled(&mut self, doubled: Token<'input>, alone: Token<'input>) -> Token<'input> {
+    if self.peek_char() == self.ch {
+      self.read_char();
+      doubled
+    } else {
+      alone
+    }
+  }
+
+  /// Skip whitespace and comments. An unterminated block comment swallows the
+  /// rest of the input, and its offset is returned as error.
+  fn skip_trivia(&mut self) -> Result<(), usize> {
+    loop {
+      match (self.ch, self.peek_char()) {
+        (' ' | '\t' | '\n' | '\r', _) => self.read_char(),
+        ('/', '/') => self.read_line_comment(),
+        ('/', '*') => self.read_block_comment()?,
+        _ => return Ok(()),
+      }
+    }
+  }
+
+  fn read_line_comment(&mut self) {
+    let from = self.pos;
+    while self.ch != '\n' && self.pos < self.input.len() {
+      self.read_char();
+    }
+    let text = &self.input[from..self.pos];
+    // `////` is a plain comment, like in Rust
+    let kind = if text.starts_with("///") && !text.starts_with("////") {
+      TriviaKind::DocComment
+    } else {
+      TriviaKind::LineComment
+    };
+    self.push_trivia(kind, from);
+  }
+
+  fn read_block_comment(&mut self) -> Result<(), usize> {
+    let from = self.pos;
+    let mut depth = 0;
+    loop {
+      match (self.ch, self.peek_char()) {
+        ('/', '*') => {
+          depth += 1;
+          self.read_char();
+        }
+        ('*', '/') => {
+          depth -= 1;
+          self.read_char();
+        }
+        _ if self.pos >= self.input.len() => return Err(from),
+        _ => {}
+      }
       self.read_char();
+      if depth == 0 {
+        break;
+      }
+    }
+    self.push_trivia(TriviaKind::BlockComment, from);
+    Ok(())
+  }
+
+  /// Record the comment from `from` up to the current char.
+  fn push_trivia(&mut self, kind: TriviaKind, from: usize) {
+    if self.retain_trivia {
+      let end = self.pos.min(self.input.len());
+      self.trivia.push(Trivia {
+        kind,
+        text: &self.input[from..end],
+        span: Span::new(from, end),
+      });
     }
   }
 }
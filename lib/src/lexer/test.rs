@@ -1,4 +1,5 @@
-use crate::token::Token;
+use crate::span::Span;
+use crate::token::{LexError, Token, Trivia, TriviaKind};
 
 #[test]
 fn tokenize() {
@@ -8,7 +9,7 @@ fn tokenize() {
     x + y;
   };
   let result = add(five, ten);
-  !-/*1;
+  !-/ *1;
   2 < 3 > 2;
 
   if (4 < 5) {
@@ -115,6 +116,634 @@ fn tokenize() {
   for tt in expected {
     let tok = lexer.move_to_next_tok();
 
-    assert_eq!(tt, tok);
+    assert_eq!(tt, tok.node);
   }
 }
+
+#[test]
+fn token_spans() {
+  let input = "let five = 5;\n  five == 10";
+
+  let expected = vec![
+    (Token::Let, Span::new(0, 3)),
+    (Token::Ident("five"), Span::new(4, 8)),
+    (Token::Assign, Span::new(9, 10)),
+    (Token::Int(5), Span::new(11, 12)),
+    (Token::Semicolon, Span::new(12, 13)),
+    (Token::Ident("five"), Span::new(16, 20)),
+    (Token::Equal, Span::new(21, 23)),
+    (Token::Int(10), Span::new(24, 26)),
+    (Token::Eof, Span::new(26, 26)),
+    (Token::Eof, Span::new(26, 26)),
+  ];
+
+  let mut lexer = super::Lexer::new(input);
+  for (tt, span) in expected {
+    let tok = lexer.move_to_next_tok();
+
+    assert_eq!(tt, tok.node);
+    assert_eq!(span, tok.span);
+  }
+}
+
+#[test]
+fn illegal_tokens() {
+  let input = "@ é 99999999999999999999 x";
+
+  #[cfg(not(feature = "bigint"))]
+  let overflow = Token::Illegal(LexError::IntegerOverflow(
+    "99999999999999999999".to_string(),
+  ));
+  #[cfg(feature = "bigint")]
+  let overflow = Token::BigInt("99999999999999999999".parse().unwrap());
+
+  let expected = vec![
+    (
+      Token::Illegal(LexError::IllegalCharacter('@')),
+      Span::new(0, 1),
+    ),
+    (
+      Token::Illegal(LexError::IllegalCharacter('é')),
+      Span::new(2, 4),
+    ),
+    (overflow, Span::new(5, 25)),
+    (Token::Ident("x"), Span::new(26, 27)),
+  ];
+
+  let mut lexer = super::Lexer::new(input);
+  for (tt, span) in expected {
+    let tok = lexer.move_to_next_tok();
+
+    assert_eq!(tt, tok.node);
+    assert_eq!(span, tok.span);
+  }
+}
+
+#[test]
+fn string_literals() {
+  let input = r#""" "foo bar" "a\"b\\c" "\n\t\r\0" "\u{1F600}\u{e9}" "héllo" "#;
+
+  let expected = vec![
+    (Token::Str("".to_string()), Span::new(0, 2)),
+    (Token::Str("foo bar".to_string()), Span::new(3, 12)),
+    (Token::Str("a\"b\\c".to_string()), Span::new(13, 22)),
+    (Token::Str("\n\t\r\0".to_string()), Span::new(23, 33)),
+    (Token::Str("\u{1F600}\u{e9}".to_string()), Span::new(34, 51)),
+    (Token::Str("héllo".to_string()), Span::new(52, 60)),
+    (Token::Eof, Span::new(61, 61)),
+  ];
+
+  let mut lexer = super::Lexer::new(input);
+  for (tt, span) in expected {
+    let tok = lexer.move_to_next_tok();
+
+    assert_eq!(tt, tok.node);
+    assert_eq!(span, tok.span);
+  }
+}
+
+#[test]
+fn illegal_string_literals() {
+  let cases = vec![
+    (r#""abc"#, LexError::UnterminatedString),
+    (r#""abc\"#, LexError::UnterminatedString),
+    (r#""a\qb" x"#, LexError::InvalidEscape(r"\q".to_string())),
+    (r#""\é""#, LexError::InvalidEscape(r"\é".to_string())),
+    (r#""\u41""#, LexError::InvalidEscape(r"\u".to_string())),
+    (r#""\u{}""#, LexError::InvalidEscape(r"\u{}".to_string())),
+    (
+      r#""\u{1234567}""#,
+      LexError::InvalidEscape(r"\u{1234567}".to_string()),
+    ),
+    (
+      r#""\u{d800}""#,
+      LexError::InvalidEscape(r"\u{d800}".to_string()),
+    ),
+    (r#""\u{12""#, LexError::InvalidEscape(r"\u{12".to_string())),
+  ];
+
+  for (input, error) in cases {
+    let mut lexer = super::Lexer::new(input);
+
+    assert_eq!(
+      lexer.move_to_next_tok().node,
+      Token::Illegal(error),
+      "{}",
+      input
+    );
+  }
+}
+
+#[test]
+fn comparison_and_unicode_operators() {
+  let input = "<= >= < > ≤≥ ≠ × ÷ !=";
+
+  let expected = vec![
+    (Token::LessThanEqual, Span::new(0, 2)),
+    (Token::GreaterThanEqual, Span::new(3, 5)),
+    (Token::LessThan, Span::new(6, 7)),
+    (Token::GreaterThan, Span::new(8, 9)),
+    (Token::LessThanEqual, Span::new(10, 13)),
+    (Token::GreaterThanEqual, Span::new(13, 16)),
+    (Token::NotEqual, Span::new(17, 20)),
+    (Token::Asterisk, Span::new(21, 23)),
+    (Token::Slash, Span::new(24, 26)),
+    (Token::NotEqual, Span::new(27, 29)),
+    (Token::Eof, Span::new(29, 29)),
+  ];
+
+  let mut lexer = super::Lexer::new(input);
+  for (tt, span) in expected {
+    let tok = lexer.move_to_next_tok();
+
+    assert_eq!(tt, tok.node);
+    assert_eq!(span, tok.span);
+  }
+}
+
+#[test]
+fn power_operator() {
+  let input = "2 ** 3 * 4 ***";
+
+  let expected = vec![
+    (Token::Int(2), Span::new(0, 1)),
+    (Token::Power, Span::new(2, 4)),
+    (Token::Int(3), Span::new(5, 6)),
+    (Token::Asterisk, Span::new(7, 8)),
+    (Token::Int(4), Span::new(9, 10)),
+    (Token::Power, Span::new(11, 13)),
+    (Token::Asterisk, Span::new(13, 14)),
+    (Token::Eof, Span::new(14, 14)),
+  ];
+
+  let mut lexer = super::Lexer::new(input);
+  for (tt, span) in expected {
+    let tok = lexer.move_to_next_tok();
+
+    assert_eq!(tt, tok.node);
+    assert_eq!(span, tok.span);
+  }
+}
+
+#[test]
+fn postfix_operators() {
+  let input = "n! != m?";
+
+  let expected = vec![
+    (Token::Ident("n"), Span::new(0, 1)),
+    (Token::Bang, Span::new(1, 2)),
+    (Token::NotEqual, Span::new(3, 5)),
+    (Token::Ident("m"), Span::new(6, 7)),
+    (Token::Question, Span::new(7, 8)),
+    (Token::Eof, Span::new(8, 8)),
+  ];
+
+  let mut lexer = super::Lexer::new(input);
+  for (tt, span) in expected {
+    let tok = lexer.move_to_next_tok();
+
+    assert_eq!(tt, tok.node);
+    assert_eq!(span, tok.span);
+  }
+}
+
+#[test]
+fn bitwise_operators() {
+  let input = "% & | ^ ~ << >> <<= >>= < >";
+
+  let expected = vec![
+    (Token::Percent, Span::new(0, 1)),
+    (Token::Ampersand, Span::new(2, 3)),
+    (Token::Pipe, Span::new(4, 5)),
+    (Token::Caret, Span::new(6, 7)),
+    (Token::Tilde, Span::new(8, 9)),
+    (Token::ShiftLeft, Span::new(10, 12)),
+    (Token::ShiftRight, Span::new(13, 15)),
+    (Token::ShiftLeftAssign, Span::new(16, 19)),
+    (Token::ShiftRightAssign, Span::new(20, 23)),
+    (Token::LessThan, Span::new(24, 25)),
+    (Token::GreaterThan, Span::new(26, 27)),
+    (Token::Eof, Span::new(27, 27)),
+  ];
+
+  let mut lexer = super::Lexer::new(input);
+  for (tt, span) in expected {
+    let tok = lexer.move_to_next_tok();
+
+    assert_eq!(tt, tok.node);
+    assert_eq!(span, tok.span);
+  }
+}
+
+#[test]
+fn logical_operators() {
+  let input = "a && b || c & |";
+
+  let expected = vec![
+    (Token::Ident("a"), Span::new(0, 1)),
+    (Token::And, Span::new(2, 4)),
+    (Token::Ident("b"), Span::new(5, 6)),
+    (Token::Or, Span::new(7, 9)),
+    (Token::Ident("c"), Span::new(10, 11)),
+    (Token::Ampersand, Span::new(12, 13)),
+    (Token::Pipe, Span::new(14, 15)),
+    (Token::Eof, Span::new(15, 15)),
+  ];
+
+  let mut lexer = super::Lexer::new(input);
+  for (tt, span) in expected {
+    let tok = lexer.move_to_next_tok();
+
+    assert_eq!(tt, tok.node);
+    assert_eq!(span, tok.span);
+  }
+}
+
+#[test]
+fn loop_keywords() {
+  let input = "while (x) { break; continue; }";
+
+  let expected = vec![
+    Token::While,
+    Token::LParen,
+    Token::Ident("x"),
+    Token::RParen,
+    Token::LBrace,
+    Token::Break,
+    Token::Semicolon,
+    Token::Continue,
+    Token::Semicolon,
+    Token::RBrace,
+    Token::Eof,
+  ];
+
+  let mut lexer = super::Lexer::new(input);
+  for tt in expected {
+    assert_eq!(tt, lexer.move_to_next_tok().node);
+  }
+}
+
+#[test]
+fn ranges() {
+  let input = "for (i in 0..10) 1..=n";
+
+  let expected = vec![
+    Token::For,
+    Token::LParen,
+    Token::Ident("i"),
+    Token::In,
+    Token::Int(0),
+    Token::DotDot,
+    Token::Int(10),
+    Token::RParen,
+    Token::Int(1),
+    Token::DotDotEqual,
+    Token::Ident("n"),
+    Token::Eof,
+  ];
+
+  let mut lexer = super::Lexer::new(input);
+  for tt in expected {
+    assert_eq!(tt, lexer.move_to_next_tok().node);
+  }
+}
+
+#[test]
+fn match_arms() {
+  let input = "match (x) { _ if x >= 1 => x, y => y }";
+
+  let expected = vec![
+    Token::Match,
+    Token::LParen,
+    Token::Ident("x"),
+    Token::RParen,
+    Token::LBrace,
+    Token::Ident("_"),
+    Token::If,
+    Token::Ident("x"),
+    Token::GreaterThanEqual,
+    Token::Int(1),
+    Token::FatArrow,
+    Token::Ident("x"),
+    Token::Comma,
+    Token::Ident("y"),
+    Token::FatArrow,
+    Token::Ident("y"),
+    Token::RBrace,
+    Token::Eof,
+  ];
+
+  let mut lexer = super::Lexer::new(input);
+  for tt in expected {
+    assert_eq!(tt, lexer.move_to_next_tok().node);
+  }
+}
+
+#[test]
+fn assignment_operators() {
+  let input = "= += -= *= ×= /= ÷= %= **= &= |= ^= <<= >>= == &&= ** *";
+
+  let expected = vec![
+    Token::Assign,
+    Token::PlusAssign,
+    Token::MinusAssign,
+    Token::AsteriskAssign,
+    Token::AsteriskAssign,
+    Token::SlashAssign,
+    Token::SlashAssign,
+    Token::PercentAssign,
+    Token::PowerAssign,
+    Token::AmpersandAssign,
+    Token::PipeAssign,
+    Token::CaretAssign,
+    Token::ShiftLeftAssign,
+    Token::ShiftRightAssign,
+    Token::Equal,
+    Token::And,
+    Token::Assign,
+    Token::Power,
+    Token::Asterisk,
+    Token::Eof,
+  ];
+
+  let mut lexer = super::Lexer::new(input);
+  for tt in expected {
+    assert_eq!(tt, lexer.move_to_next_tok().node);
+  }
+}
+
+#[test]
+fn skip_comments() {
+  let input = "// leading
+  let x = /* inline */ 1; /// doc
+  /* outer /* nested */ still a comment */ x // trailing";
+
+  let expected = vec![
+    Token::Let,
+    Token::Ident("x"),
+    Token::Assign,
+    Token::Int(1),
+    Token::Semicolon,
+    Token::Ident("x"),
+    Token::Eof,
+  ];
+
+  let mut lexer = super::Lexer::new(input);
+  for tt in expected {
+    let tok = lexer.read_with_trivia();
+
+    assert_eq!(tt, tok.token.node);
+    assert!(tok.leading_trivia.is_empty());
+  }
+}
+
+#[test]
+fn retain_trivia() {
+  let input = "/// doc\n//// plain\nx /* a /* b */ */ / y // end";
+
+  let expected = vec![
+    (
+      Token::Ident("x"),
+      vec![
+        (TriviaKind::DocComment, "/// doc", Span::new(0, 7)),
+        (TriviaKind::LineComment, "//// plain", Span::new(8, 18)),
+      ],
+    ),
+    (
+      Token::Slash,
+      vec![(
+        TriviaKind::BlockComment,
+        "/* a /* b */ */",
+        Span::new(21, 36),
+      )],
+    ),
+    (Token::Ident("y"), vec![]),
+    (
+      Token::Eof,
+      vec![(TriviaKind::LineComment, "// end", Span::new(41, 47))],
+    ),
+  ];
+
+  let mut lexer = super::Lexer::new(input).with_trivia();
+  for (tt, trivia) in expected {
+    let tok = lexer.read_with_trivia();
+    let trivia = trivia
+      .into_iter()
+      .map(|(kind, text, span)| Trivia { kind, text, span })
+      .collect::<Vec<_>>();
+
+    assert_eq!(tt, tok.token.node);
+    assert_eq!(trivia, tok.leading_trivia);
+  }
+}
+
+#[test]
+fn round_trip_trivia() {
+  let input = "/// adds\nlet add = fn(a, b) { /* sum */ a + b }; // done\n";
+
+  let mut lexer = super::Lexer::new(input).with_trivia();
+  let mut pieces = vec![];
+  loop {
+    let tok = lexer.read_with_trivia();
+    pieces.extend(tok.leading_trivia.iter().map(|trivia| trivia.span));
+    if tok.token.node == Token::Eof {
+      break;
+    }
+    pieces.push(tok.token.span);
+  }
+
+  // everything between the pieces is whitespace
+  let mut end = 0;
+  for span in pieces {
+    assert!(input[end..span.start].trim().is_empty());
+    end = span.end;
+  }
+  assert!(input[end..].trim().is_empty());
+}
+
+#[test]
+fn unterminated_comment() {
+  let cases = vec![
+    ("1 /* a", Span::new(2, 6)),
+    ("/* a /* b */", Span::new(0, 12)),
+    ("/*/", Span::new(0, 3)),
+  ];
+
+  for (input, span) in cases {
+    let mut lexer = super::Lexer::new(input);
+    let mut tok = lexer.move_to_next_tok();
+    if tok.node == Token::Int(1) {
+      tok = lexer.move_to_next_tok();
+    }
+
+    assert_eq!(
+      tok.node,
+      Token::Illegal(LexError::UnterminatedComment),
+      "{}",
+      input
+    );
+    assert_eq!(tok.span, span, "{}", input);
+    assert_eq!(lexer.move_to_next_tok().node, Token::Eof, "{}", input);
+  }
+}
+
+#[test]
+fn number_literals() {
+  let input = "0 1.5 2e10 2.5E-3 1e+2 1. .5 1..2";
+
+  let expected = vec![
+    (Token::Int(0), Span::new(0, 1)),
+    (Token::Float(1.5), Span::new(2, 5)),
+    (Token::Float(2e10), Span::new(6, 10)),
+    (Token::Float(2.5e-3), Span::new(11, 17)),
+    (Token::Float(100.0), Span::new(18, 22)),
+    // neither `1.` nor `.5` is a float
+    (Token::Int(1), Span::new(23, 24)),
+    (
+      Token::Illegal(LexError::IllegalCharacter('.')),
+      Span::new(24, 25),
+    ),
+    (
+      Token::Illegal(LexError::IllegalCharacter('.')),
+      Span::new(26, 27),
+    ),
+    (Token::Int(5), Span::new(27, 28)),
+    // `1..2` is a range
+    (Token::Int(1), Span::new(29, 30)),
+    (Token::DotDot, Span::new(30, 32)),
+    (Token::Int(2), Span::new(32, 33)),
+  ];
+
+  let mut lexer = super::Lexer::new(input);
+  for (tt, span) in expected {
+    let tok = lexer.move_to_next_tok();
+
+    assert_eq!(tt, tok.node);
+    assert_eq!(span, tok.span);
+  }
+}
+
+#[test]
+fn invalid_float_literals() {
+  let cases = vec![
+    ("1e", "1e"),
+    ("1e+", "1e+"),
+    ("2.5ex", "2.5ex"),
+    ("1e999", "1e999"),
+  ];
+
+  for (input, literal) in cases {
+    let mut lexer = super::Lexer::new(input);
+
+    assert_eq!(
+      lexer.move_to_next_tok().node,
+      Token::Illegal(LexError::InvalidFloat(literal.to_string())),
+      "{}",
+      input
+    );
+    assert_eq!(lexer.move_to_next_tok().node, Token::Eof, "{}", input);
+  }
+}
+
+#[test]
+fn prefixed_and_separated_integers() {
+  let input = "0xff 0xDead_Beef 0o17 0b1010 0b_1 1_000_000 1_0.5_0 1e1_0 0x7fff_ffff_ffff_ffff";
+
+  let expected = vec![
+    (Token::Int(0xff), Span::new(0, 4)),
+    (Token::Int(0xdead_beef), Span::new(5, 16)),
+    (Token::Int(0o17), Span::new(17, 21)),
+    (Token::Int(0b1010), Span::new(22, 28)),
+    (Token::Int(1), Span::new(29, 33)),
+    (Token::Int(1_000_000), Span::new(34, 43)),
+    (Token::Float(10.5), Span::new(44, 51)),
+    (Token::Float(1e10), Span::new(52, 57)),
+    (Token::Int(i64::MAX), Span::new(58, 79)),
+  ];
+
+  let mut lexer = super::Lexer::new(input);
+  for (tt, span) in expected {
+    let tok = lexer.move_to_next_tok();
+
+    assert_eq!(tt, tok.node);
+    assert_eq!(span, tok.span);
+  }
+}
+
+#[test]
+fn invalid_integer_literals() {
+  let cases = vec![
+    ("0x", LexError::InvalidInteger("0x".to_string())),
+    ("0x_", LexError::InvalidInteger("0x_".to_string())),
+    ("0xfg", LexError::InvalidInteger("0xfg".to_string())),
+    ("0o8", LexError::InvalidInteger("0o8".to_string())),
+    ("0b102", LexError::InvalidInteger("0b102".to_string())),
+  ];
+
+  for (input, error) in cases {
+    let mut lexer = super::Lexer::new(input);
+
+    assert_eq!(
+      lexer.move_to_next_tok().node,
+      Token::Illegal(error),
+      "{}",
+      input
+    );
+    assert_eq!(lexer.move_to_next_tok().node, Token::Eof, "{}", input);
+  }
+}
+
+#[test]
+#[cfg(not(feature = "bigint"))]
+fn integer_overflow() {
+  let cases = vec![
+    (
+      "9_223_372_036_854_775_808",
+      LexError::IntegerOverflow("9_223_372_036_854_775_808".to_string()),
+    ),
+    (
+      "0x8000000000000000",
+      LexError::IntegerOverflow("0x8000000000000000".to_string()),
+    ),
+    (
+      "0b1_0000000000000000000000000000000000000000000000000000000000000000",
+      LexError::IntegerOverflow(
+        "0b1_0000000000000000000000000000000000000000000000000000000000000000".to_string(),
+      ),
+    ),
+  ];
+
+  for (input, error) in cases {
+    let mut lexer = super::Lexer::new(input);
+
+    assert_eq!(
+      lexer.move_to_next_tok().node,
+      Token::Illegal(error),
+      "{}",
+      input
+    );
+    assert_eq!(lexer.move_to_next_tok().node, Token::Eof, "{}", input);
+  }
+}
+
+#[test]
+#[cfg(feature = "bigint")]
+fn big_integer_literals() {
+  let input = "9_223_372_036_854_775_808 0x1_0000_0000_0000_0000 0b1_0000000000000000000000000000000000000000000000000000000000000000 99999999999999999999";
+
+  let expected = vec![
+    ("9223372036854775808", Span::new(0, 25)),
+    ("18446744073709551616", Span::new(26, 49)),
+    ("18446744073709551616", Span::new(50, 118)),
+    ("99999999999999999999", Span::new(119, 139)),
+  ];
+
+  let mut lexer = super::Lexer::new(input);
+  for (value, span) in expected {
+    let tok = lexer.move_to_next_tok();
+
+    assert_eq!(Token::BigInt(value.parse().unwrap()), tok.node);
+    assert_eq!(span, tok.span);
+  }
+  assert_eq!(lexer.move_to_next_tok().node, Token::Eof);
+}
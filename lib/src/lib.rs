@@ -1,4 +1,7 @@
 pub mod ast;
+pub mod checker;
+pub mod diagnostic;
 pub mod lexer;
 pub mod parser;
-mod token;
+pub mod span;
+pub mod token;
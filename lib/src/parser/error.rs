@@ -1,35 +1,253 @@
+use crate::diagnostic::{Diagnostic, Label};
+use crate::span::Span;
+use crate::token::Token;
 use std::fmt;
 
-#[derive(Debug, Clone)]
-pub enum ParseErrorKind {
-  UnexpectedToken,
+/// Something the parser was looking for when it found another token.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expected {
+  Token(Token<'static>),
+  Identifier,
+  Expression,
+  Pattern,
+}
+
+impl fmt::Display for Expected {
+  fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      Expected::Token(tok) => write!(formatter, "`{}`", tok),
+      Expected::Identifier => write!(formatter, "identifier"),
+      Expected::Expression => write!(formatter, "expression"),
+      Expected::Pattern => write!(formatter, "pattern"),
+    }
+  }
+}
+
+/// Describe a token found in the source for error messages.
+struct Found<'token, 'input>(&'token Token<'input>);
+
+impl fmt::Display for Found<'_, '_> {
+  fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+    match self.0 {
+      Token::Eof => write!(formatter, "end of input"),
+      tok => write!(formatter, "`{}`", tok),
+    }
+  }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParseErrorKind<'input> {
+  /// A token which isn't any of the `expected` ones.
+  UnexpectedToken {
+    expected: Vec<Expected>,
+    found: Token<'input>,
+  },
+  /// A closing delimiter is missing, `open` is where its counterpart is.
+  MissingDelimiter {
+    delimiter: Token<'static>,
+    open: Span,
+    found: Token<'input>,
+  },
+  /// The input ends inside of a block, `open` is where its `{` is.
+  UnterminatedBlock { open: Span },
+  /// A malformed integer literal, e.g. with digits its base doesn't have.
+  InvalidIntegerLiteral { literal: String },
+  /// An integer literal which doesn't fit into an integer.
+  IntegerOverflow { literal: String },
+  /// A float literal which is malformed or doesn't fit into a float.
+  InvalidFloatLiteral { literal: String },
+  /// A token which can't start an expression.
+  NoPrefixParser { found: Token<'input> },
+  /// A character which can't start any token.
+  IllegalCharacter { found: char },
+  /// A string literal which isn't closed before the end of the input.
+  UnterminatedString,
+  /// An unknown or malformed escape sequence in a string literal.
+  InvalidEscape { escape: String },
+  /// A block comment which isn't closed before the end of the input.
+  UnterminatedComment,
+  /// A comma right before the closing delimiter of a list.
+  TrailingComma,
+  /// The left-hand side of an assignment which can't be assigned to.
+  InvalidAssignmentTarget,
+  /// The parser gave up after reporting `limit` errors.
+  TooManyErrors { limit: usize },
+}
+
+impl ParseErrorKind<'_> {
+  /// A stable code identifying the kind of error, which is safe to match on
+  /// in tooling. Codes are never reused for another kind of error.
+  pub fn code(&self) -> &'static str {
+    match self {
+      ParseErrorKind::UnexpectedToken { .. } => "E0001",
+      ParseErrorKind::MissingDelimiter { .. } => "E0002",
+      ParseErrorKind::UnterminatedBlock { .. } => "E0003",
+      ParseErrorKind::InvalidIntegerLiteral { .. } => "E0004",
+      ParseErrorKind::NoPrefixParser { .. } => "E0005",
+      ParseErrorKind::IllegalCharacter { .. } => "E0006",
+      ParseErrorKind::TrailingComma => "E0007",
+      ParseErrorKind::TooManyErrors { .. } => "E0008",
+      ParseErrorKind::UnterminatedString => "E0009",
+      ParseErrorKind::InvalidEscape { .. } => "E0010",
+      ParseErrorKind::UnterminatedComment => "E0011",
+      ParseErrorKind::InvalidFloatLiteral { .. } => "E0012",
+      ParseErrorKind::IntegerOverflow { .. } => "E0013",
+      ParseErrorKind::InvalidAssignmentTarget => "E0014",
+    }
+  }
 }
 
 // implement fmt.Display trait for ParseErrorKind type
-impl fmt::Display for ParseErrorKind {
+impl fmt::Display for ParseErrorKind<'_> {
   fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
-    match *self {
-      ParseErrorKind::UnexpectedToken => write!(formatter, "Unexpected Token"),
+    match self {
+      ParseErrorKind::UnexpectedToken { expected, found } => {
+        let expected = expected
+          .iter()
+          .map(|expected| expected.to_string())
+          .collect::<Vec<_>>();
+        match expected.as_slice() {
+          [expected] => write!(formatter, "expected {}", expected)?,
+          expected => write!(formatter, "expected one of {}", expected.join(", "))?,
+        }
+        write!(formatter, ", found {}", Found(found))
+      }
+      ParseErrorKind::MissingDelimiter {
+        delimiter, found, ..
+      } => write!(
+        formatter,
+        "expected `{}`, found {}",
+        delimiter,
+        Found(found)
+      ),
+      ParseErrorKind::UnterminatedBlock { .. } => write!(formatter, "unterminated block"),
+      ParseErrorKind::InvalidIntegerLiteral { literal } => {
+        write!(formatter, "invalid integer literal `{}`", literal)
+      }
+      ParseErrorKind::IntegerOverflow { literal } => {
+        write!(formatter, "integer literal `{}` is too large", literal)
+      }
+      ParseErrorKind::InvalidFloatLiteral { literal } => {
+        write!(formatter, "invalid float literal `{}`", literal)
+      }
+      ParseErrorKind::NoPrefixParser { found } => {
+        write!(formatter, "expected expression, found {}", Found(found))
+      }
+      ParseErrorKind::IllegalCharacter { found } => {
+        write!(formatter, "illegal character `{}`", found.escape_debug())
+      }
+      ParseErrorKind::UnterminatedString => write!(formatter, "unterminated string literal"),
+      ParseErrorKind::InvalidEscape { escape } => {
+        write!(formatter, "invalid escape sequence `{}`", escape)
+      }
+      ParseErrorKind::UnterminatedComment => write!(formatter, "unterminated block comment"),
+      ParseErrorKind::TrailingComma => write!(formatter, "trailing comma"),
+      ParseErrorKind::InvalidAssignmentTarget => {
+        write!(formatter, "invalid left-hand side of assignment")
+      }
+      ParseErrorKind::TooManyErrors { limit } => {
+        write!(formatter, "aborting after {} errors", limit)
+      }
     }
   }
 }
 
-#[derive(Debug, Clone)]
-pub struct ParseError {
-  kind: ParseErrorKind,
-  msg: String,
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError<'input> {
+  kind: ParseErrorKind<'input>,
+  span: Span,
 }
 
-impl ParseError {
-  pub fn new(kind: ParseErrorKind, msg: String) -> Self {
-    ParseError { kind, msg }
+impl<'input> ParseError<'input> {
+  pub fn new(kind: ParseErrorKind<'input>, span: Span) -> Self {
+    ParseError { kind, span }
+  }
+
+  pub fn kind(&self) -> &ParseErrorKind<'input> {
+    &self.kind
+  }
+
+  /// See `ParseErrorKind::code`.
+  pub fn code(&self) -> &'static str {
+    self.kind.code()
+  }
+
+  pub fn message(&self) -> String {
+    self.kind.to_string()
+  }
+
+  /// Where the error occurred in the source.
+  pub fn span(&self) -> Span {
+    self.span
+  }
+
+  pub fn to_diagnostic(&self) -> Diagnostic {
+    let diagnostic = Diagnostic::error(self.message()).with_code(self.code());
+    match &self.kind {
+      ParseErrorKind::UnexpectedToken { expected, .. } => {
+        let label = match expected.as_slice() {
+          [expected] => format!("expected {}", expected),
+          _ => "unexpected token".to_string(),
+        };
+        diagnostic.with_label(Label::primary(self.span, label))
+      }
+      ParseErrorKind::MissingDelimiter {
+        delimiter, open, ..
+      } => diagnostic
+        .with_label(Label::primary(
+          self.span,
+          format!("expected `{}`", delimiter),
+        ))
+        .with_label(Label::secondary(*open, "unclosed delimiter")),
+      ParseErrorKind::UnterminatedBlock { open } => diagnostic
+        .with_label(Label::primary(self.span, "expected `}`"))
+        .with_label(Label::secondary(*open, "block starts here")),
+      ParseErrorKind::InvalidIntegerLiteral { .. } => diagnostic
+        .with_label(Label::primary(self.span, "invalid integer literal"))
+        .with_note("hex, octal and binary literals start with `0x`, `0o` and `0b`, and need digits of their base"),
+      ParseErrorKind::IntegerOverflow { .. } => diagnostic
+        .with_label(Label::primary(self.span, "doesn't fit into an integer"))
+        .with_note(format!(
+          "integer literals can be at most {}, a `-` in front of one is an operator, so {} has to be written as `{} - 1`",
+          i64::MAX,
+          i64::MIN,
+          -i64::MAX
+        )),
+      ParseErrorKind::InvalidFloatLiteral { .. } => diagnostic
+        .with_label(Label::primary(self.span, "invalid float literal"))
+        .with_note("an exponent needs digits, e.g. `1e10`, and floats must be finite"),
+      ParseErrorKind::NoPrefixParser { .. } => {
+        diagnostic.with_label(Label::primary(self.span, "expected expression"))
+      }
+      ParseErrorKind::IllegalCharacter { .. } => {
+        diagnostic.with_label(Label::primary(self.span, "illegal character"))
+      }
+      ParseErrorKind::UnterminatedString => diagnostic
+        .with_label(Label::primary(self.span, "string starts here"))
+        .with_help("add a closing `\"`"),
+      ParseErrorKind::InvalidEscape { .. } => diagnostic
+        .with_label(Label::primary(self.span, "in this string literal"))
+        .with_note(r#"valid escapes are `\n`, `\t`, `\r`, `\0`, `\"`, `\\` and `\u{...}`"#),
+      ParseErrorKind::UnterminatedComment => diagnostic
+        .with_label(Label::primary(self.span, "comment starts here"))
+        .with_help("add a closing `*/`, block comments nest"),
+      ParseErrorKind::TrailingComma => diagnostic
+        .with_label(Label::primary(self.span, "trailing comma"))
+        .with_help("remove the trailing comma"),
+      ParseErrorKind::InvalidAssignmentTarget => diagnostic
+        .with_label(Label::primary(self.span, "can't be assigned to"))
+        .with_help("assign to a variable or an element of one, e.g. `a[i]`"),
+      ParseErrorKind::TooManyErrors { .. } => {
+        diagnostic.with_label(Label::primary(self.span, "stopped parsing here"))
+      }
+    }
   }
 }
 
-impl fmt::Display for ParseError {
+impl fmt::Display for ParseError<'_> {
   fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
-    write!(formatter, "{}: {}", self.kind, self.msg)
+    write!(formatter, "error[{}]: {}", self.code(), self.kind)
   }
 }
 
-pub type ParseErrors = Vec<ParseError>;
+pub type ParseErrors<'input> = Vec<ParseError<'input>>;
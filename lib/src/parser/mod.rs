@@ -2,10 +2,16 @@ mod error;
 #[cfg(test)]
 mod test;
 
+pub use error::{Expected, ParseError, ParseErrorKind, ParseErrors};
+
 use crate::ast;
 use crate::lexer::Lexer;
-use crate::token::Token;
-use error::*;
+use crate::span::{Span, Spanned};
+use crate::token::{LexError, Token};
+
+/// Parsing is abandoned once this many errors have been reported, since the
+/// later ones are unlikely to be helpful.
+const MAX_ERRORS: usize = 20;
 
 /// The parsing approach is called Top Down Operator Precedence Parsing, or
 /// Pratt parsing, was invented as an alternative to parsers based on
@@ -21,21 +27,29 @@ pub struct Parser<'input> {
   /// the next token in the input.
   lexer: Lexer<'input>,
   // The last of tokens we have read.
-  current_token: Token<'input>,
+  current_token: Spanned<Token<'input>>,
   /// next token, to decide whether we are at the end of the line or if we are
   /// at just the start of an arithmetic expression.
-  next_token: Token<'input>,
-  errors: ParseErrors,
-  stmts: ast::Program<'input>,
+  next_token: Spanned<Token<'input>>,
+  errors: ParseErrors<'input>,
+  /// Set once an error is reported, and cleared once the parser has skipped to
+  /// the next statement. Errors reported in between are most likely caused by
+  /// the first one, so they're dropped.
+  panic_mode: bool,
+  /// How many braces, of blocks or hashes, the tokens read so far leave open.
+  brace_depth: usize,
+  stmts: ast::Program,
 }
 
-impl Parser<'_> {
-  pub fn new(lexer: Lexer) -> Parser {
+impl<'input> Parser<'input> {
+  pub fn new(lexer: Lexer<'input>) -> Parser<'input> {
     let mut parser = Parser {
       lexer,
-      current_token: Token::Eof,
-      next_token: Token::Eof,
+      current_token: Token::Eof.into(),
+      next_token: Token::Eof.into(),
       errors: vec![],
+      panic_mode: false,
+      brace_depth: 0,
       stmts: vec![],
     };
     // read 2 tokens, so current_token and next_token are both set.
@@ -45,18 +59,28 @@ impl Parser<'_> {
     parser
   }
 
-  /// We're using a loop to parse statements until we encounter a Eof character
+  /// We're using a loop to parse statements until we encounter a Eof character.
+  ///
+  /// Malformed statements are reported and kept in the program as
+  /// `ast::Statement::Error` nodes, so the program is always as complete as
+  /// possible.
   pub fn parse(&mut self) -> &Self {
     while !self.current_token_is(&Token::Eof) {
-      if let Some(stmt) = self.parse_stmt() {
-        self.stmts.push(stmt);
+      if self.errors.len() >= MAX_ERRORS {
+        self.errors.push(ParseError::new(
+          ParseErrorKind::TooManyErrors { limit: MAX_ERRORS },
+          self.current_token.span,
+        ));
+        break;
       }
+      let stmt = self.parse_stmt();
+      self.stmts.push(stmt);
       self.move_to_next_tok();
     }
     self
   }
 
-  pub fn get_errors(&self) -> ParseErrors {
+  pub fn get_errors(&self) -> ParseErrors<'input> {
     self.errors.clone()
   }
 
@@ -71,68 +95,159 @@ impl<'input> Parser<'input> {
   fn move_to_next_tok(&mut self) {
     self.current_token = self.next_token.clone();
     self.next_token = self.lexer.move_to_next_tok();
+    match self.current_token.node {
+      Token::LBrace => self.brace_depth += 1,
+      Token::RBrace => self.brace_depth = self.brace_depth.saturating_sub(1),
+      _ => {}
+    }
   }
 
   /// try to match next token, and move on.
-  fn expect_next_is(&mut self, tok: Token) -> bool {
+  fn expect_next_is(&mut self, tok: Token<'static>) -> bool {
     if self.next_token_is(&tok) {
       self.move_to_next_tok();
       true
     } else {
-      self.error_next_token(tok);
+      self.error_next_token(vec![Expected::Token(tok)]);
+      false
+    }
+  }
+
+  /// Like `expect_next_is`, for the delimiter closing the one at `open`.
+  fn expect_closing(&mut self, delimiter: Token<'static>, open: Span) -> bool {
+    if self.next_token_is(&delimiter) {
+      self.move_to_next_tok();
+      true
+    } else {
+      self.error(ParseError::new(
+        ParseErrorKind::MissingDelimiter {
+          delimiter,
+          open,
+          found: self.next_token.node.clone(),
+        },
+        self.next_token.span,
+      ));
       false
     }
   }
 
   fn current_token_is(&self, tok: &Token) -> bool {
-    self.current_token == *tok
+    self.current_token.node == *tok
   }
 
   fn next_token_is(&self, tok: &Token) -> bool {
-    self.next_token == *tok
+    self.next_token.node == *tok
+  }
+
+  fn error(&mut self, error: ParseError<'input>) {
+    if !self.panic_mode && self.errors.len() < MAX_ERRORS {
+      self.errors.push(error);
+    }
+    self.panic_mode = true;
   }
 
-  fn error_next_token(&mut self, tok: Token) {
-    self.errors.push(ParseError::new(
-      ParseErrorKind::UnexpectedToken,
-      format!(
-        "expected next token to be {:?}, got {:?} instead.",
-        tok, self.next_token
-      ),
+  fn error_next_token(&mut self, expected: Vec<Expected>) {
+    self.error(ParseError::new(
+      ParseErrorKind::UnexpectedToken {
+        expected,
+        found: self.next_token.node.clone(),
+      },
+      self.next_token.span,
     ));
   }
 
-  fn parse_ident(&self) -> Option<ast::Ident<'input>> {
-    match &self.current_token {
-      Token::Ident(ident) => Some(ast::Ident(*ident)),
+  fn error_current_token(&mut self, expected: Vec<Expected>) {
+    self.error(ParseError::new(
+      ParseErrorKind::UnexpectedToken {
+        expected,
+        found: self.current_token.node.clone(),
+      },
+      self.current_token.span,
+    ));
+  }
+
+  /// Wrap a node which starts at `start` and ends with the current token.
+  fn spanned<T>(&self, node: T, start: Span) -> Spanned<T> {
+    Spanned::new(node, start.to(self.current_token.span))
+  }
+
+  fn parse_ident(&self) -> Option<Spanned<ast::Ident>> {
+    match &self.current_token.node {
+      Token::Ident(ident) => Some(Spanned::new(
+        ast::Ident::from(*ident),
+        self.current_token.span,
+      )),
       _ => None,
     }
   }
+
+  /// This is equivalent to `self.expect_next_is(Token::Ident(...))`, which
+  /// can't be called since it needs wildcard matching.
+  fn expect_next_ident(&mut self) -> Option<Spanned<ast::Ident>> {
+    match &self.next_token.node {
+      Token::Ident(_) => {
+        self.move_to_next_tok();
+        self.parse_ident()
+      }
+      _ => {
+        self.error_next_token(vec![Expected::Identifier]);
+        None
+      }
+    }
+  }
 }
 
 // statements
 impl<'input> Parser<'input> {
-  fn parse_stmt(&mut self) -> Option<ast::Statement<'input>> {
-    match self.current_token {
-      Token::Let => self.parse_let_stmt(),
+  /// Parse a statement. A malformed one is turned into an error node which
+  /// covers everything up to the next statement boundary.
+  fn parse_stmt(&mut self) -> Spanned<ast::Statement> {
+    let start = self.current_token.span;
+    // braces opened before the statement, which may start with one itself
+    let depth = self.brace_depth - usize::from(self.current_token_is(&Token::LBrace));
+    let stmt = match self.current_token.node {
+      Token::Let | Token::Const => self.parse_let_stmt(),
       Token::Return => self.parse_return_stmt(),
+      Token::Break | Token::Continue => self.parse_loop_control_stmt(),
       _ => self.parse_expr_stmt(),
+    };
+    match stmt {
+      Some(stmt) if !self.panic_mode => self.spanned(stmt, start),
+      _ => {
+        self.synchronize(depth);
+        self.spanned(ast::Statement::Error, start)
+      }
     }
   }
 
-  fn parse_let_stmt(&mut self) -> Option<ast::Statement<'input>> {
-    // This is equivalent to self.expect_next_is(Token::Ident(...))
-    match &self.next_token {
-      // The reason we don't call self.expect_next_is function is we need
-      // wildcard matching.
-      Token::Ident(_) => self.move_to_next_tok(),
-      _ => {
-        self.error_next_token(Token::Ident("<Identifier literal>"));
-        return None;
+  /// Panic-mode recovery: skip tokens until the current one ends a statement
+  /// or the next one can start one (or closes the enclosing block). Braces
+  /// opened since the statement started at `depth` are skipped as a whole.
+  fn synchronize(&mut self, depth: usize) {
+    while self.brace_depth > depth || !self.current_token_is(&Token::Semicolon) {
+      match self.next_token.node {
+        Token::Eof => break,
+        Token::Let
+        | Token::Const
+        | Token::Return
+        | Token::Break
+        | Token::Continue
+        | Token::RBrace
+          if self.brace_depth == depth =>
+        {
+          break
+        }
+        _ => {}
       }
-    };
+      self.move_to_next_tok();
+    }
+    self.panic_mode = false;
+  }
 
-    let name = self.parse_ident()?;
+  /// `let` and `const` statements only differ in their keyword.
+  fn parse_let_stmt(&mut self) -> Option<ast::Statement> {
+    let is_const = self.current_token_is(&Token::Const);
+    let name = self.expect_next_ident()?;
 
     if !self.expect_next_is(Token::Assign) {
       return None;
@@ -144,19 +259,37 @@ impl<'input> Parser<'input> {
     if self.next_token_is(&Token::Semicolon) {
       self.move_to_next_tok();
     }
-    Some(ast::Statement::Let(name, value_expr))
+    Some(if is_const {
+      ast::Statement::Const(name, value_expr)
+    } else {
+      ast::Statement::Let(name, value_expr)
+    })
   }
 
-  fn parse_return_stmt(&mut self) -> Option<ast::Statement<'input>> {
+  fn parse_return_stmt(&mut self) -> Option<ast::Statement> {
     self.move_to_next_tok();
     let value_expr = self.parse_expr(ast::Precedence::Lowest)?;
-    while !self.current_token_is(&Token::Semicolon) {
+    if self.next_token_is(&Token::Semicolon) {
       self.move_to_next_tok();
     }
     Some(ast::Statement::Return(value_expr))
   }
 
-  fn parse_expr_stmt(&mut self) -> Option<ast::Statement<'input>> {
+  /// `break` or `continue`, whether they're in a loop is only known at
+  /// runtime.
+  fn parse_loop_control_stmt(&mut self) -> Option<ast::Statement> {
+    let stmt = match self.current_token.node {
+      Token::Break => ast::Statement::Break,
+      Token::Continue => ast::Statement::Continue,
+      _ => return None,
+    };
+    if self.next_token_is(&Token::Semicolon) {
+      self.move_to_next_tok();
+    }
+    Some(stmt)
+  }
+
+  fn parse_expr_stmt(&mut self) -> Option<ast::Statement> {
     if let Some(expr) = self.parse_expr(ast::Precedence::Lowest) {
       // expression semicolon is optional
       if self.next_token_is(&Token::Semicolon) {
@@ -168,55 +301,125 @@ impl<'input> Parser<'input> {
     }
   }
 
-  fn parse_block_stmt(&mut self) -> Option<ast::BlockStatement<'input>> {
+  fn parse_block_stmt(&mut self) -> Option<ast::BlockStatement> {
+    let open = self.current_token.span;
     self.move_to_next_tok();
-    let mut stmts: ast::BlockStatement<'input> = vec![];
+    let mut stmts: ast::BlockStatement = vec![];
     while !self.current_token_is(&Token::RBrace) && !self.current_token_is(&Token::Eof) {
-      if let Some(stmt) = self.parse_stmt() {
-        stmts.push(stmt);
-      }
+      let stmt = self.parse_stmt();
+      stmts.push(stmt);
       self.move_to_next_tok();
     }
 
+    if self.current_token_is(&Token::Eof) {
+      self.error(ParseError::new(
+        ParseErrorKind::UnterminatedBlock { open },
+        self.current_token.span,
+      ));
+      return None;
+    }
+
     Some(stmts)
   }
 }
 
 // expressions
 impl<'input> Parser<'input> {
-  fn parse_expr(&mut self, precedence: ast::Precedence) -> Option<ast::Expr<'input>> {
+  fn parse_expr(&mut self, precedence: ast::Precedence) -> Option<Spanned<ast::Expr>> {
+    let start = self.current_token.span;
     // handle prefix
-    let mut left_expr = match self.current_token {
+    let mut left_expr = match self.current_token.node {
       Token::Ident(_) => self.parse_ident_expr(),
       Token::Int(_) => self.parse_int_expr(),
-      Token::Minus | Token::Plus | Token::Bang => self.parse_prefix_expr(),
+      #[cfg(feature = "bigint")]
+      Token::BigInt(_) => self.parse_int_expr(),
+      Token::Float(_) => self.parse_float_expr(),
+      Token::Minus | Token::Plus | Token::Bang | Token::Tilde => self.parse_prefix_expr(),
       Token::Bool(_) => self.parse_bool_expr(),
+      Token::Str(_) => self.parse_str_expr(),
       Token::LParen => self.parse_grouped_expr(),
       Token::If => self.parse_if_expr(),
+      Token::While => self.parse_while_expr(),
+      Token::For => self.parse_for_expr(),
+      Token::Match => self.parse_match_expr(),
       Token::Function => self.parse_function_literal(),
+      Token::LBracket => self.parse_array_literal(),
+      // blocks only follow keywords like `if`, or the `=>` of a match arm,
+      // which parse them on their own, so a brace in front of an expression
+      // always opens a hash
+      Token::LBrace => self.parse_hash_literal(),
       _ => {
         // unexpected token type
         self.error_no_prefix_parser();
         None
       }
-    };
+    }
+    .map(|expr| self.spanned(expr, start));
 
     // handle infix
     while !self.next_token_is(&Token::Semicolon) && precedence < self.next_token_precedence() {
-      match self.next_token {
+      match self.next_token.node {
         Token::Plus
         | Token::Minus
         | Token::Slash
         | Token::Asterisk
+        | Token::Power
+        | Token::Percent
+        | Token::Ampersand
+        | Token::Pipe
+        | Token::Caret
+        | Token::ShiftLeft
+        | Token::ShiftRight
         | Token::Equal
         | Token::NotEqual
         | Token::LessThan
         | Token::LessThanEqual
         | Token::GreaterThan
-        | Token::GreaterThanEqual => {
+        | Token::GreaterThanEqual
+        | Token::And
+        | Token::Or
+        | Token::DotDot
+        | Token::DotDotEqual => {
+          self.move_to_next_tok();
+          left_expr = if let Some(expr) = left_expr {
+            let start = expr.span;
+            self
+              .parse_infix_expr(expr)
+              .map(|expr| self.spanned(expr, start))
+          } else {
+            None
+          }
+        }
+        Token::Assign
+        | Token::PlusAssign
+        | Token::MinusAssign
+        | Token::AsteriskAssign
+        | Token::SlashAssign
+        | Token::PercentAssign
+        | Token::PowerAssign
+        | Token::AmpersandAssign
+        | Token::PipeAssign
+        | Token::CaretAssign
+        | Token::ShiftLeftAssign
+        | Token::ShiftRightAssign => {
+          self.move_to_next_tok();
+          left_expr = if let Some(expr) = left_expr {
+            let start = expr.span;
+            self
+              .parse_assign_expr(expr)
+              .map(|expr| self.spanned(expr, start))
+          } else {
+            None
+          }
+        }
+        // in front of an expression, `!` is a prefix operator instead
+        Token::Bang | Token::Question => {
           self.move_to_next_tok();
           left_expr = if let Some(expr) = left_expr {
-            self.parse_infix_expr(expr)
+            let start = expr.span;
+            self
+              .parse_postfix_expr(expr)
+              .map(|expr| self.spanned(expr, start))
           } else {
             None
           }
@@ -224,7 +427,21 @@ impl<'input> Parser<'input> {
         Token::LParen => {
           self.move_to_next_tok();
           left_expr = if let Some(expr) = left_expr {
-            self.parse_call_expr(expr)
+            let start = expr.span;
+            self
+              .parse_call_expr(expr)
+              .map(|expr| self.spanned(expr, start))
+          } else {
+            None
+          }
+        }
+        Token::LBracket => {
+          self.move_to_next_tok();
+          left_expr = if let Some(expr) = left_expr {
+            let start = expr.span;
+            self
+              .parse_index_expr(expr)
+              .map(|expr| self.spanned(expr, start))
           } else {
             None
           }
@@ -237,38 +454,69 @@ impl<'input> Parser<'input> {
   }
 
   fn error_no_prefix_parser(&mut self) {
-    self.errors.push(ParseError::new(
-      ParseErrorKind::UnexpectedToken,
-      format!(
-        "no prefix parse function for {:?} found",
-        self.current_token
-      ),
-    ))
+    let kind = match &self.current_token.node {
+      Token::Illegal(LexError::IllegalCharacter(ch)) => {
+        ParseErrorKind::IllegalCharacter { found: *ch }
+      }
+      Token::Illegal(LexError::InvalidInteger(literal)) => ParseErrorKind::InvalidIntegerLiteral {
+        literal: literal.clone(),
+      },
+      Token::Illegal(LexError::IntegerOverflow(literal)) => ParseErrorKind::IntegerOverflow {
+        literal: literal.clone(),
+      },
+      Token::Illegal(LexError::InvalidFloat(literal)) => ParseErrorKind::InvalidFloatLiteral {
+        literal: literal.clone(),
+      },
+      Token::Illegal(LexError::UnterminatedString) => ParseErrorKind::UnterminatedString,
+      Token::Illegal(LexError::InvalidEscape(escape)) => ParseErrorKind::InvalidEscape {
+        escape: escape.clone(),
+      },
+      Token::Illegal(LexError::UnterminatedComment) => ParseErrorKind::UnterminatedComment,
+      tok => ParseErrorKind::NoPrefixParser { found: tok.clone() },
+    };
+    self.error(ParseError::new(kind, self.current_token.span))
+  }
+
+  fn parse_ident_expr(&self) -> Option<ast::Expr> {
+    self.parse_ident().map(|ident| ast::Expr::Ident(ident.node))
   }
 
-  fn parse_ident_expr(&self) -> Option<ast::Expr<'input>> {
-    self.parse_ident().map(ast::Expr::Ident)
+  fn parse_int_expr(&self) -> Option<ast::Expr> {
+    match &self.current_token.node {
+      Token::Int(literal) => Some(ast::Expr::Literal(ast::Literal::Int(*literal))),
+      #[cfg(feature = "bigint")]
+      Token::BigInt(literal) => Some(ast::Expr::Literal(ast::Literal::BigInt(literal.clone()))),
+      _ => None,
+    }
   }
 
-  fn parse_int_expr(&self) -> Option<ast::Expr<'input>> {
-    match self.current_token {
-      Token::Int(literal) => Some(ast::Expr::Literal(ast::Literal::Int(literal))),
+  fn parse_float_expr(&self) -> Option<ast::Expr> {
+    match self.current_token.node {
+      Token::Float(literal) => Some(ast::Expr::Literal(ast::Literal::Float(literal))),
       _ => None,
     }
   }
 
-  fn parse_bool_expr(&self) -> Option<ast::Expr<'input>> {
-    match self.current_token {
+  fn parse_bool_expr(&self) -> Option<ast::Expr> {
+    match self.current_token.node {
       Token::Bool(literal) => Some(ast::Expr::Literal(ast::Literal::Bool(literal))),
       _ => None,
     }
   }
 
-  fn parse_prefix_expr(&mut self) -> Option<ast::Expr<'input>> {
-    let prefix = match self.current_token {
+  fn parse_str_expr(&self) -> Option<ast::Expr> {
+    match &self.current_token.node {
+      Token::Str(literal) => Some(ast::Expr::Literal(ast::Literal::Str(literal.clone()))),
+      _ => None,
+    }
+  }
+
+  fn parse_prefix_expr(&mut self) -> Option<ast::Expr> {
+    let prefix = match self.current_token.node {
       Token::Bang => ast::Prefix::Bang,
       Token::Minus => ast::Prefix::Minus,
       Token::Plus => ast::Prefix::Plus,
+      Token::Tilde => ast::Prefix::BitNot,
       _ => return None,
     };
 
@@ -279,50 +527,125 @@ impl<'input> Parser<'input> {
       .map(|expr| ast::Expr::Prefix(prefix, Box::new(expr)))
   }
 
-  fn parse_infix_expr(&mut self, left_expr: ast::Expr<'input>) -> Option<ast::Expr<'input>> {
-    let infix = match self.current_token {
+  fn parse_postfix_expr(&self, expr: Spanned<ast::Expr>) -> Option<ast::Expr> {
+    let postfix = match self.current_token.node {
+      Token::Bang => ast::Postfix::Factorial,
+      Token::Question => ast::Postfix::Try,
+      _ => return None,
+    };
+
+    Some(ast::Expr::Postfix(Box::new(expr), postfix))
+  }
+
+  fn parse_infix_expr(&mut self, left_expr: Spanned<ast::Expr>) -> Option<ast::Expr> {
+    let infix = match self.current_token.node {
       Token::Plus => ast::Infix::Plus,
       Token::Minus => ast::Infix::Minus,
       Token::Slash => ast::Infix::Divide,
       Token::Asterisk => ast::Infix::Multiply,
+      Token::Power => ast::Infix::Power,
+      Token::Percent => ast::Infix::Modulo,
+      Token::Ampersand => ast::Infix::BitAnd,
+      Token::Pipe => ast::Infix::BitOr,
+      Token::Caret => ast::Infix::BitXor,
+      Token::ShiftLeft => ast::Infix::ShiftLeft,
+      Token::ShiftRight => ast::Infix::ShiftRight,
       Token::Equal => ast::Infix::Equal,
       Token::NotEqual => ast::Infix::NotEqual,
       Token::LessThan => ast::Infix::LessThan,
       Token::LessThanEqual => ast::Infix::LessThanEqual,
       Token::GreaterThan => ast::Infix::GreaterThan,
       Token::GreaterThanEqual => ast::Infix::GreaterThanEqual,
+      Token::And => ast::Infix::And,
+      Token::Or => ast::Infix::Or,
+      Token::DotDot => ast::Infix::Range,
+      Token::DotDotEqual => ast::Infix::RangeInclusive,
       _ => return None,
     };
 
-    let precedence = self.current_token_precedence();
+    let precedence = match self.token_to_associativity(&self.current_token.node) {
+      ast::Associativity::Left => self.current_token_precedence(),
+      ast::Associativity::Right => self.current_token_precedence().lower(),
+    };
     self.move_to_next_tok();
     self
       .parse_expr(precedence)
       .map(|expr| ast::Expr::Infix(Box::new(left_expr), infix, Box::new(expr)))
   }
 
-  fn parse_grouped_expr(&mut self) -> Option<ast::Expr<'input>> {
+  fn parse_assign_expr(&mut self, target: Spanned<ast::Expr>) -> Option<ast::Expr> {
+    let operator = match self.current_token.node {
+      Token::Assign => None,
+      Token::PlusAssign => Some(ast::Infix::Plus),
+      Token::MinusAssign => Some(ast::Infix::Minus),
+      Token::AsteriskAssign => Some(ast::Infix::Multiply),
+      Token::SlashAssign => Some(ast::Infix::Divide),
+      Token::PercentAssign => Some(ast::Infix::Modulo),
+      Token::PowerAssign => Some(ast::Infix::Power),
+      Token::AmpersandAssign => Some(ast::Infix::BitAnd),
+      Token::PipeAssign => Some(ast::Infix::BitOr),
+      Token::CaretAssign => Some(ast::Infix::BitXor),
+      Token::ShiftLeftAssign => Some(ast::Infix::ShiftLeft),
+      Token::ShiftRightAssign => Some(ast::Infix::ShiftRight),
+      _ => return None,
+    };
+
+    if !Self::is_assignable(&target.node) {
+      self.error(ParseError::new(
+        ParseErrorKind::InvalidAssignmentTarget,
+        target.span,
+      ));
+      return None;
+    }
+
+    let precedence = match self.token_to_associativity(&self.current_token.node) {
+      ast::Associativity::Left => self.current_token_precedence(),
+      ast::Associativity::Right => self.current_token_precedence().lower(),
+    };
+    self.move_to_next_tok();
+    self.parse_expr(precedence).map(|value| ast::Expr::Assign {
+      target: Box::new(target),
+      operator,
+      value: Box::new(value),
+    })
+  }
+
+  /// Only identifiers, and elements of what can be assigned to, can be
+  /// assigned to.
+  fn is_assignable(expr: &ast::Expr) -> bool {
+    match expr {
+      ast::Expr::Ident(_) => true,
+      ast::Expr::Index { left, .. } => Self::is_assignable(&left.node),
+      _ => false,
+    }
+  }
+
+  /// The parentheses don't make a node of their own, the inner expression is
+  /// widened to cover them instead.
+  fn parse_grouped_expr(&mut self) -> Option<ast::Expr> {
+    let open = self.current_token.span;
     self.move_to_next_tok();
     let expr = self.parse_expr(ast::Precedence::Lowest);
 
-    if self.expect_next_is(Token::RParen) {
-      expr
+    if self.expect_closing(Token::RParen, open) {
+      expr.map(|expr| expr.node)
     } else {
       None
     }
   }
 
-  fn parse_if_expr(&mut self) -> Option<ast::Expr<'input>> {
+  fn parse_if_expr(&mut self) -> Option<ast::Expr> {
     if !self.expect_next_is(Token::LParen) {
       return None;
     }
 
+    let open = self.current_token.span;
     self.move_to_next_tok();
     let condition = self.parse_expr(ast::Precedence::Lowest);
     // https://doc.rust-lang.org/reference/expressions/operator-expr.html#the-question-mark-operator
     condition.as_ref()?;
 
-    if !self.expect_next_is(Token::RParen) {
+    if !self.expect_closing(Token::RParen, open) {
       return None;
     }
 
@@ -330,22 +653,17 @@ impl<'input> Parser<'input> {
       return None;
     }
 
-    let consequence = if let Some(stmt) = self.parse_block_stmt() {
-      stmt
-    } else {
-      vec![]
-    };
+    let consequence = self.parse_block_stmt()?;
 
-    let mut alternative: Option<ast::BlockStatement<'input>> = None;
+    let mut alternative: Option<ast::BlockStatement> = None;
     if self.next_token_is(&Token::Else) {
       self.move_to_next_tok();
 
       if !self.expect_next_is(Token::LBrace) {
-        self.error_next_token(Token::LBrace);
         return None;
       }
 
-      alternative = self.parse_block_stmt();
+      alternative = Some(self.parse_block_stmt()?);
     }
 
     Some(ast::Expr::If {
@@ -355,65 +673,261 @@ impl<'input> Parser<'input> {
     })
   }
 
-  fn parse_call_expr(&mut self, func: ast::Expr<'input>) -> Option<ast::Expr<'input>> {
-    let arguments = self.parse_call_args()?;
+  fn parse_while_expr(&mut self) -> Option<ast::Expr> {
+    if !self.expect_next_is(Token::LParen) {
+      return None;
+    }
+
+    let open = self.current_token.span;
+    self.move_to_next_tok();
+    let condition = self.parse_expr(ast::Precedence::Lowest)?;
+
+    if !self.expect_closing(Token::RParen, open) {
+      return None;
+    }
+
+    if !self.expect_next_is(Token::LBrace) {
+      return None;
+    }
+
+    let body = self.parse_block_stmt()?;
+
+    Some(ast::Expr::While {
+      condition: Box::new(condition),
+      body,
+    })
+  }
+
+  fn parse_for_expr(&mut self) -> Option<ast::Expr> {
+    if !self.expect_next_is(Token::LParen) {
+      return None;
+    }
+
+    let open = self.current_token.span;
+    let binding = self.parse_for_binding()?;
+
+    if !self.expect_next_is(Token::In) {
+      return None;
+    }
+
+    self.move_to_next_tok();
+    let iterable = self.parse_expr(ast::Precedence::Lowest)?;
+
+    if !self.expect_closing(Token::RParen, open) {
+      return None;
+    }
+
+    if !self.expect_next_is(Token::LBrace) {
+      return None;
+    }
+
+    let body = self.parse_block_stmt()?;
+
+    Some(ast::Expr::For {
+      binding,
+      iterable: Box::new(iterable),
+      body,
+    })
+  }
+
+  /// `x` or `k, v`
+  fn parse_for_binding(&mut self) -> Option<ast::ForBinding> {
+    let first = self.expect_next_ident()?;
+    if !self.next_token_is(&Token::Comma) {
+      return Some(ast::ForBinding::Item(first));
+    }
+
+    self.move_to_next_tok();
+    let second = self.expect_next_ident()?;
+    Some(ast::ForBinding::Entry(first, second))
+  }
+
+  fn parse_match_expr(&mut self) -> Option<ast::Expr> {
+    if !self.expect_next_is(Token::LParen) {
+      return None;
+    }
+
+    let open = self.current_token.span;
+    self.move_to_next_tok();
+    let subject = self.parse_expr(ast::Precedence::Lowest)?;
+
+    if !self.expect_closing(Token::RParen, open) {
+      return None;
+    }
+
+    if !self.expect_next_is(Token::LBrace) {
+      return None;
+    }
+
+    let open = self.current_token.span;
+    let mut arms = vec![];
+    while !self.next_token_is(&Token::RBrace) {
+      self.move_to_next_tok();
+      arms.push(self.parse_match_arm()?);
+
+      if !self.next_token_is(&Token::Comma) {
+        break;
+      }
+      self.move_to_next_tok();
+      self.expect_no_trailing_comma(&Token::RBrace)?;
+    }
+
+    if !self.expect_closing(Token::RBrace, open) {
+      return None;
+    }
+
+    Some(ast::Expr::Match {
+      subject: Box::new(subject),
+      arms,
+    })
+  }
+
+  fn parse_match_arm(&mut self) -> Option<ast::MatchArm> {
+    let pattern = self.parse_pattern()?;
+
+    let mut guard = None;
+    if self.next_token_is(&Token::If) {
+      self.move_to_next_tok();
+      self.move_to_next_tok();
+      guard = Some(self.parse_expr(ast::Precedence::Lowest)?);
+    }
+
+    if !self.expect_next_is(Token::FatArrow) {
+      return None;
+    }
+
+    // a hash as the body has to be wrapped in parentheses
+    let body = if self.next_token_is(&Token::LBrace) {
+      self.move_to_next_tok();
+      self.parse_block_stmt()?
+    } else {
+      self.move_to_next_tok();
+      let expr = self.parse_expr(ast::Precedence::Lowest)?;
+      vec![Spanned::new(ast::Statement::Expr(expr.clone()), expr.span)]
+    };
+
+    Some(ast::MatchArm {
+      pattern,
+      guard,
+      body,
+    })
+  }
+
+  fn parse_call_expr(&mut self, func: Spanned<ast::Expr>) -> Option<ast::Expr> {
+    let arguments = self.parse_expr_list(Token::RParen)?;
     Some(ast::Expr::Call {
       function: Box::new(func),
       arguments,
     })
   }
 
-  fn parse_call_args(&mut self) -> Option<Vec<ast::Expr<'input>>> {
-    let mut args = vec![];
-    if self.next_token_is(&Token::RParen) {
+  fn parse_array_literal(&mut self) -> Option<ast::Expr> {
+    self.parse_expr_list(Token::RBracket).map(ast::Expr::Array)
+  }
+
+  fn parse_hash_literal(&mut self) -> Option<ast::Expr> {
+    let open = self.current_token.span;
+    let mut pairs = vec![];
+
+    while !self.next_token_is(&Token::RBrace) {
+      self.move_to_next_tok();
+      let key = self.parse_expr(ast::Precedence::Lowest)?;
+      if !self.expect_next_is(Token::Colon) {
+        return None;
+      }
+      self.move_to_next_tok();
+      let value = self.parse_expr(ast::Precedence::Lowest)?;
+      pairs.push((key, value));
+
+      if !self.next_token_is(&Token::Comma) {
+        break;
+      }
+      self.move_to_next_tok();
+      self.expect_no_trailing_comma(&Token::RBrace)?;
+    }
+
+    if !self.expect_closing(Token::RBrace, open) {
+      return None;
+    }
+
+    Some(ast::Expr::Hash(pairs))
+  }
+
+  fn parse_index_expr(&mut self, left: Spanned<ast::Expr>) -> Option<ast::Expr> {
+    let open = self.current_token.span;
+    self.move_to_next_tok();
+    let index = self.parse_expr(ast::Precedence::Lowest)?;
+
+    if !self.expect_closing(Token::RBracket, open) {
+      return None;
+    }
+
+    Some(ast::Expr::Index {
+      left: Box::new(left),
+      index: Box::new(index),
+    })
+  }
+
+  /// Parse comma separated expressions, from the current opening delimiter up
+  /// to the `closing` one, e.g. call arguments or array elements.
+  fn parse_expr_list(&mut self, closing: Token<'static>) -> Option<Vec<Spanned<ast::Expr>>> {
+    let open = self.current_token.span;
+    let mut exprs = vec![];
+    if self.next_token_is(&closing) {
       self.move_to_next_tok();
-      return Some(args);
+      return Some(exprs);
     }
 
     self.move_to_next_tok();
-    args.push(self.parse_expr(ast::Precedence::Lowest)?);
+    exprs.push(self.parse_expr(ast::Precedence::Lowest)?);
 
     while self.next_token_is(&Token::Comma) {
-      for _ in 0..=1 {
-        self.move_to_next_tok();
-      }
-      args.push(self.parse_expr(ast::Precedence::Lowest)?);
+      self.move_to_next_tok();
+      self.expect_no_trailing_comma(&closing)?;
+      self.move_to_next_tok();
+      exprs.push(self.parse_expr(ast::Precedence::Lowest)?);
     }
 
-    if !self.expect_next_is(Token::RParen) {
+    if !self.expect_closing(closing, open) {
       return None;
     }
 
-    Some(args)
+    Some(exprs)
+  }
+
+  /// Reject a comma, the current token, which is directly followed by the
+  /// `closing` delimiter of a list.
+  fn expect_no_trailing_comma(&mut self, closing: &Token) -> Option<()> {
+    if self.next_token_is(closing) {
+      self.error(ParseError::new(
+        ParseErrorKind::TrailingComma,
+        self.current_token.span,
+      ));
+      return None;
+    }
+    Some(())
   }
 }
 
 impl<'input> Parser<'input> {
-  fn parse_function_literal(&mut self) -> Option<ast::Expr<'input>> {
+  fn parse_function_literal(&mut self) -> Option<ast::Expr> {
     if !self.expect_next_is(Token::LParen) {
       return None;
     }
 
-    let params = if let Some(params) = self.parse_function_params() {
-      params
-    } else {
-      vec![]
-    };
+    let params = self.parse_function_params()?;
 
     if !self.expect_next_is(Token::LBrace) {
       return None;
     }
 
-    let body = if let Some(body) = self.parse_block_stmt() {
-      body
-    } else {
-      vec![]
-    };
+    let body = self.parse_block_stmt()?;
 
     Some(ast::Expr::Function { params, body })
   }
 
-  fn parse_function_params(&mut self) -> Option<Vec<ast::Ident<'input>>> {
+  fn parse_function_params(&mut self) -> Option<Vec<Spanned<ast::Ident>>> {
+    let open = self.current_token.span;
     let mut identifiers = vec![];
     if self.next_token_is(&Token::RParen) {
       self.move_to_next_tok();
@@ -424,24 +938,134 @@ impl<'input> Parser<'input> {
       self.move_to_next_tok();
     }
 
-    if let Some(ident) = self.parse_ident() {
-      identifiers.push(ident);
-    }
+    identifiers.push(self.parse_function_param()?);
 
     while self.next_token_is(&Token::Comma) {
-      for _ in 0..=1 {
-        self.move_to_next_tok();
+      self.move_to_next_tok();
+      self.expect_no_trailing_comma(&Token::RParen)?;
+      self.move_to_next_tok();
+      identifiers.push(self.parse_function_param()?);
+    }
+
+    if !self.expect_closing(Token::RParen, open) {
+      return None;
+    }
+
+    Some(identifiers)
+  }
+
+  fn parse_function_param(&mut self) -> Option<Spanned<ast::Ident>> {
+    let param = self.parse_ident();
+    if param.is_none() {
+      self.error_current_token(vec![Expected::Identifier]);
+    }
+    param
+  }
+}
+
+// patterns
+impl<'input> Parser<'input> {
+  fn parse_pattern(&mut self) -> Option<Spanned<ast::Pattern>> {
+    let start = self.current_token.span;
+    let pattern = match self.current_token.node {
+      Token::Ident("_") => ast::Pattern::Wildcard,
+      Token::Ident(name) => ast::Pattern::Binding(name.into()),
+      Token::LBracket => self.parse_array_pattern()?,
+      Token::LBrace => self.parse_hash_pattern()?,
+      _ => ast::Pattern::Literal(self.parse_literal()?.node),
+    };
+    Some(self.spanned(pattern, start))
+  }
+
+  /// A literal, numbers may be negative.
+  fn parse_literal(&mut self) -> Option<Spanned<ast::Literal>> {
+    let start = self.current_token.span;
+    let negative = self.current_token_is(&Token::Minus);
+    if negative {
+      self.move_to_next_tok();
+    }
+
+    let literal = match (&self.current_token.node, negative) {
+      (Token::Int(val), _) => ast::Literal::Int(if negative { -val } else { *val }),
+      #[cfg(feature = "bigint")]
+      (Token::BigInt(val), _) => ast::Literal::BigInt(if negative { -val } else { val.clone() }),
+      (Token::Float(val), _) => ast::Literal::Float(if negative { -val } else { *val }),
+      (Token::Bool(val), false) => ast::Literal::Bool(*val),
+      (Token::Str(val), false) => ast::Literal::Str(val.clone()),
+      _ => {
+        self.error_current_token(vec![Expected::Pattern]);
+        return None;
+      }
+    };
+    Some(self.spanned(literal, start))
+  }
+
+  fn parse_array_pattern(&mut self) -> Option<ast::Pattern> {
+    let open = self.current_token.span;
+    let mut elements = vec![];
+    let mut rest = None;
+
+    while !self.next_token_is(&Token::RBracket) {
+      self.move_to_next_tok();
+      // the rest of the elements can only come last
+      if self.current_token_is(&Token::DotDot) {
+        rest = Some(Box::new(self.parse_rest_pattern()));
+        break;
       }
-      if let Some(ident) = self.parse_ident() {
-        identifiers.push(ident);
+      elements.push(self.parse_pattern()?);
+
+      if !self.next_token_is(&Token::Comma) {
+        break;
       }
+      self.move_to_next_tok();
+      self.expect_no_trailing_comma(&Token::RBracket)?;
     }
 
-    if !self.expect_next_is(Token::RParen) {
+    if !self.expect_closing(Token::RBracket, open) {
       return None;
     }
 
-    Some(identifiers)
+    Some(ast::Pattern::Array { elements, rest })
+  }
+
+  /// `..` or `..<identifier>`
+  fn parse_rest_pattern(&mut self) -> Spanned<ast::Pattern> {
+    let start = self.current_token.span;
+    let pattern = match self.next_token.node {
+      Token::Ident("_") => ast::Pattern::Wildcard,
+      Token::Ident(name) => ast::Pattern::Binding(name.into()),
+      _ => return self.spanned(ast::Pattern::Wildcard, start),
+    };
+    self.move_to_next_tok();
+    self.spanned(pattern, start)
+  }
+
+  fn parse_hash_pattern(&mut self) -> Option<ast::Pattern> {
+    let open = self.current_token.span;
+    let mut pairs = vec![];
+
+    while !self.next_token_is(&Token::RBrace) {
+      self.move_to_next_tok();
+      let key = self.parse_literal()?;
+      if !self.expect_next_is(Token::Colon) {
+        return None;
+      }
+      self.move_to_next_tok();
+      let pattern = self.parse_pattern()?;
+      pairs.push((key, pattern));
+
+      if !self.next_token_is(&Token::Comma) {
+        break;
+      }
+      self.move_to_next_tok();
+      self.expect_no_trailing_comma(&Token::RBrace)?;
+    }
+
+    if !self.expect_closing(Token::RBrace, open) {
+      return None;
+    }
+
+    Some(ast::Pattern::Hash(pairs))
   }
 }
 
@@ -449,21 +1073,99 @@ impl<'input> Parser<'input> {
 impl Parser<'_> {
   fn token_to_precedence(&self, tok: &Token) -> ast::Precedence {
     match tok {
+      Token::Assign
+      | Token::PlusAssign
+      | Token::MinusAssign
+      | Token::AsteriskAssign
+      | Token::SlashAssign
+      | Token::PercentAssign
+      | Token::PowerAssign
+      | Token::AmpersandAssign
+      | Token::PipeAssign
+      | Token::CaretAssign
+      | Token::ShiftLeftAssign
+      | Token::ShiftRightAssign => ast::Precedence::Assign,
+      Token::DotDot | Token::DotDotEqual => ast::Precedence::Range,
+      Token::Or => ast::Precedence::LogicalOr,
+      Token::And => ast::Precedence::LogicalAnd,
+      Token::Pipe => ast::Precedence::BitOr,
+      Token::Caret => ast::Precedence::BitXor,
+      Token::Ampersand => ast::Precedence::BitAnd,
       Token::Equal | Token::NotEqual => ast::Precedence::Equals,
-      Token::LessThan | Token::GreaterThan => ast::Precedence::LessGreater,
+      Token::LessThan | Token::LessThanEqual | Token::GreaterThan | Token::GreaterThanEqual => {
+        ast::Precedence::LessGreater
+      }
+      Token::ShiftLeft | Token::ShiftRight => ast::Precedence::Shift,
       Token::Plus | Token::Minus => ast::Precedence::Sum,
-      Token::Slash | Token::Asterisk => ast::Precedence::Product,
+      Token::Slash | Token::Asterisk | Token::Percent => ast::Precedence::Product,
+      Token::Power => ast::Precedence::Power,
+      Token::Bang | Token::Question => ast::Precedence::Postfix,
       Token::LParen => ast::Precedence::Call,
+      Token::LBracket => ast::Precedence::Index,
 
       _ => ast::Precedence::Lowest,
     }
   }
 
+  fn token_to_associativity(&self, tok: &Token) -> ast::Associativity {
+    match tok {
+      Token::Power
+      | Token::Assign
+      | Token::PlusAssign
+      | Token::MinusAssign
+      | Token::AsteriskAssign
+      | Token::SlashAssign
+      | Token::PercentAssign
+      | Token::PowerAssign
+      | Token::AmpersandAssign
+      | Token::PipeAssign
+      | Token::CaretAssign
+      | Token::ShiftLeftAssign
+      | Token::ShiftRightAssign => ast::Associativity::Right,
+      _ => ast::Associativity::Left,
+    }
+  }
+
   fn current_token_precedence(&self) -> ast::Precedence {
-    self.token_to_precedence(&self.current_token)
+    self.token_to_precedence(&self.current_token.node)
   }
 
   fn next_token_precedence(&self) -> ast::Precedence {
-    self.token_to_precedence(&self.next_token)
+    match self.next_token.node {
+      Token::Bang if self.next_bang_starts_expr() => ast::Precedence::Lowest,
+      ref tok => self.token_to_precedence(tok),
+    }
+  }
+
+  /// Semicolons are optional, so a `!` after an expression is either its
+  /// factorial or a prefix of the next statement. It's the latter if the `!`s
+  /// are followed by a token which starts an expression, e.g. in
+  /// `let a = 5 !a`. Calling or indexing a factorial is never valid, so this
+  /// includes `(` and `[`, but not `-`, which is parsed as a subtraction from
+  /// the factorial. Such a statement has to be preceded by a `;`.
+  fn next_bang_starts_expr(&self) -> bool {
+    let mut lexer = self.lexer.clone();
+    loop {
+      match lexer.move_to_next_tok().node {
+        Token::Bang => {}
+        Token::Ident(_)
+        | Token::Int(_)
+        | Token::Float(_)
+        | Token::Bool(_)
+        | Token::Str(_)
+        | Token::Tilde
+        | Token::If
+        | Token::While
+        | Token::For
+        | Token::Match
+        | Token::Function
+        | Token::LParen
+        | Token::LBracket
+        | Token::LBrace => return true,
+        #[cfg(feature = "bigint")]
+        Token::BigInt(_) => return true,
+        _ => return false,
+      }
+    }
   }
 }
@@ -1,6 +1,15 @@
-use crate::ast::{Expr, Ident, Infix, Literal, Prefix, Statement as Stmt};
+use crate::ast::{
+  Expr, ForBinding, Ident, Infix, Literal, MatchArm, Pattern, Postfix, Prefix, Span, Spanned,
+  Statement as Stmt,
+};
 use crate::lexer::Lexer;
-use crate::parser::Parser;
+use crate::parser::{Expected, ParseErrorKind, Parser};
+use crate::token::Token;
+
+/// Wrap an expected node, spans don't take part in the comparison.
+fn node<T>(node: T) -> Spanned<T> {
+  node.into()
+}
 
 macro_rules! parser {
   ($arg: tt) => {{
@@ -37,16 +46,41 @@ fn let_statements() {
   assert_eq!(
     parser.stmts,
     vec![
-      Stmt::Let(Ident("x"), Expr::Literal(Literal::Int(5))),
-      Stmt::Let(Ident("y"), Expr::Literal(Literal::Int(10))),
-      Stmt::Let(
-        Ident("foobar"),
-        Expr::Infix(
-          Box::new(Expr::Ident(Ident("x"))),
+      node(Stmt::Let(
+        node(Ident::from("x")),
+        node(Expr::Literal(Literal::Int(5)))
+      )),
+      node(Stmt::Let(
+        node(Ident::from("y")),
+        node(Expr::Literal(Literal::Int(10)))
+      )),
+      node(Stmt::Let(
+        node(Ident::from("foobar")),
+        node(Expr::Infix(
+          Box::new(node(Expr::Ident(Ident::from("x")))),
           Infix::Plus,
-          Box::new(Expr::Ident(Ident("y")))
-        )
-      )
+          Box::new(node(Expr::Ident(Ident::from("y"))))
+        ))
+      ))
+    ]
+  );
+}
+
+#[test]
+fn const_statements() {
+  let parser = parser!("const x = 5; let y = x;");
+
+  assert_eq!(
+    parser.stmts,
+    vec![
+      node(Stmt::Const(
+        node(Ident::from("x")),
+        node(Expr::Literal(Literal::Int(5)))
+      )),
+      node(Stmt::Let(
+        node(Ident::from("y")),
+        node(Expr::Ident(Ident::from("x")))
+      )),
     ]
   );
 }
@@ -62,13 +96,13 @@ fn return_statements() {
   assert_eq!(
     parser.stmts,
     vec![
-      Stmt::Return(Expr::Literal(Literal::Int(5))),
-      Stmt::Return(Expr::Literal(Literal::Int(10))),
-      Stmt::Return(Expr::Infix(
-        Box::new(Expr::Ident(Ident("a"))),
+      node(Stmt::Return(node(Expr::Literal(Literal::Int(5))))),
+      node(Stmt::Return(node(Expr::Literal(Literal::Int(10))))),
+      node(Stmt::Return(node(Expr::Infix(
+        Box::new(node(Expr::Ident(Ident::from("a")))),
         Infix::Plus,
-        Box::new(Expr::Literal(Literal::Int(20)))
-      ))
+        Box::new(node(Expr::Literal(Literal::Int(20))))
+      ))))
     ]
   )
 }
@@ -77,7 +111,10 @@ fn return_statements() {
 fn ident_expr() {
   let parser = parser!("foobar;");
 
-  assert_eq!(parser.stmts, vec![Stmt::Expr(Expr::Ident(Ident("foobar")))]);
+  assert_eq!(
+    parser.stmts,
+    vec![node(Stmt::Expr(node(Expr::Ident(Ident::from("foobar")))))]
+  );
 }
 
 #[test]
@@ -86,7 +123,7 @@ fn integer_literal_expr() {
 
   assert_eq!(
     parser.stmts,
-    vec![Stmt::Expr(Expr::Literal(Literal::Int(5)))]
+    vec![node(Stmt::Expr(node(Expr::Literal(Literal::Int(5)))))]
   );
 }
 
@@ -96,21 +133,21 @@ fn bool_literal_expr() {
     (
       "true;false",
       vec![
-        Stmt::Expr(Expr::Literal(Literal::Bool(true))),
-        Stmt::Expr(Expr::Literal(Literal::Bool(false))),
+        node(Stmt::Expr(node(Expr::Literal(Literal::Bool(true))))),
+        node(Stmt::Expr(node(Expr::Literal(Literal::Bool(false))))),
       ],
     ),
     (
       "3 > 5 == false",
-      vec![Stmt::Expr(Expr::Infix(
-        Box::new(Expr::Infix(
-          Box::new(Expr::Literal(Literal::Int(3))),
+      vec![node(Stmt::Expr(node(Expr::Infix(
+        Box::new(node(Expr::Infix(
+          Box::new(node(Expr::Literal(Literal::Int(3)))),
           Infix::GreaterThan,
-          Box::new(Expr::Literal(Literal::Int(5))),
-        )),
+          Box::new(node(Expr::Literal(Literal::Int(5)))),
+        ))),
         Infix::Equal,
-        Box::new(Expr::Literal(Literal::Bool(false))),
-      ))],
+        Box::new(node(Expr::Literal(Literal::Bool(false)))),
+      ))))],
     ),
   ];
 
@@ -121,29 +158,60 @@ fn bool_literal_expr() {
   }
 }
 
+#[test]
+fn float_literal_expr() {
+  let parser = parser!("1.5 * -2e3");
+
+  assert_eq!(
+    parser.stmts,
+    vec![node(Stmt::Expr(node(Expr::Infix(
+      Box::new(node(Expr::Literal(Literal::Float(1.5)))),
+      Infix::Multiply,
+      Box::new(node(Expr::Prefix(
+        Prefix::Minus,
+        Box::new(node(Expr::Literal(Literal::Float(2e3)))),
+      ))),
+    ))))]
+  );
+}
+
+#[test]
+fn string_literal_expr() {
+  let parser = parser!(r#""hello" + "\tworld\n""#);
+
+  assert_eq!(
+    parser.stmts,
+    vec![node(Stmt::Expr(node(Expr::Infix(
+      Box::new(node(Expr::Literal(Literal::Str("hello".to_string())))),
+      Infix::Plus,
+      Box::new(node(Expr::Literal(Literal::Str("\tworld\n".to_string())))),
+    ))))]
+  );
+}
+
 #[test]
 fn prefix_expr() {
   let cases = vec![
     (
       "!5;",
-      vec![Stmt::Expr(Expr::Prefix(
+      vec![node(Stmt::Expr(node(Expr::Prefix(
         Prefix::Bang,
-        Box::new(Expr::Literal(Literal::Int(5))),
-      ))],
+        Box::new(node(Expr::Literal(Literal::Int(5)))),
+      ))))],
     ),
     (
       "+15;",
-      vec![Stmt::Expr(Expr::Prefix(
+      vec![node(Stmt::Expr(node(Expr::Prefix(
         Prefix::Plus,
-        Box::new(Expr::Literal(Literal::Int(15))),
-      ))],
+        Box::new(node(Expr::Literal(Literal::Int(15)))),
+      ))))],
     ),
     (
       "-15;",
-      vec![Stmt::Expr(Expr::Prefix(
+      vec![node(Stmt::Expr(node(Expr::Prefix(
         Prefix::Minus,
-        Box::new(Expr::Literal(Literal::Int(15))),
-      ))],
+        Box::new(node(Expr::Literal(Literal::Int(15)))),
+      ))))],
     ),
   ];
 
@@ -154,72 +222,123 @@ fn prefix_expr() {
   }
 }
 
+#[test]
+fn postfix_expr() {
+  let cases = vec![
+    (
+      "5!;",
+      vec![node(Stmt::Expr(node(Expr::Postfix(
+        Box::new(node(Expr::Literal(Literal::Int(5)))),
+        Postfix::Factorial,
+      ))))],
+    ),
+    (
+      "a?;",
+      vec![node(Stmt::Expr(node(Expr::Postfix(
+        Box::new(node(Expr::Ident(Ident::from("a")))),
+        Postfix::Try,
+      ))))],
+    ),
+  ];
+
+  for (input, expected) in cases {
+    let parser = parser!(input);
+
+    assert_eq!(parser.stmts, expected)
+  }
+
+  let cases = vec![
+    ("-3!", "-(3!)"),
+    ("!a!", "!(a!)"),
+    ("2 ** 3!", "2 ** (3!)"),
+    ("3! ** 2", "(3!) ** 2"),
+    ("n!!", "(n!)!"),
+    ("n! != 1", "(n!) != 1"),
+    ("a[0]!", "(a[0])!"),
+    ("f(x)? + 1", "((f(x))?) + 1"),
+    // a `-` is a subtraction rather than a negation of the next statement
+    ("n!\n- 1", "(n!) - 1"),
+    // a `!` in front of an expression starts the next statement
+    ("let a = 5 !a", "let a = 5; !a"),
+    ("5\n!(b)", "5; !b"),
+    ("n! (1)", "n; !1"),
+    ("5\n![b]", "5; ![b]"),
+    ("let a = 5\n!!a", "let a = 5; !(!a)"),
+    ("n! !true", "n; !(!true)"),
+    ("n!! + 1", "((n!)!) + 1"),
+  ];
+
+  for (input, grouped) in cases {
+    assert_eq!(parser!(input).stmts, parser!(grouped).stmts, "{}", input);
+  }
+}
+
 #[test]
 fn infix_expr() {
   let cases = vec![
     (
       "5 + 5",
-      vec![Stmt::Expr(Expr::Infix(
-        Box::new(Expr::Literal(Literal::Int(5))),
+      vec![node(Stmt::Expr(node(Expr::Infix(
+        Box::new(node(Expr::Literal(Literal::Int(5)))),
         Infix::Plus,
-        Box::new(Expr::Literal(Literal::Int(5))),
-      ))],
+        Box::new(node(Expr::Literal(Literal::Int(5)))),
+      ))))],
     ),
     (
       "5 - 5",
-      vec![Stmt::Expr(Expr::Infix(
-        Box::new(Expr::Literal(Literal::Int(5))),
+      vec![node(Stmt::Expr(node(Expr::Infix(
+        Box::new(node(Expr::Literal(Literal::Int(5)))),
         Infix::Minus,
-        Box::new(Expr::Literal(Literal::Int(5))),
-      ))],
+        Box::new(node(Expr::Literal(Literal::Int(5)))),
+      ))))],
     ),
     (
       "5 * 5",
-      vec![Stmt::Expr(Expr::Infix(
-        Box::new(Expr::Literal(Literal::Int(5))),
+      vec![node(Stmt::Expr(node(Expr::Infix(
+        Box::new(node(Expr::Literal(Literal::Int(5)))),
         Infix::Multiply,
-        Box::new(Expr::Literal(Literal::Int(5))),
-      ))],
+        Box::new(node(Expr::Literal(Literal::Int(5)))),
+      ))))],
     ),
     (
       "5 / 5",
-      vec![Stmt::Expr(Expr::Infix(
-        Box::new(Expr::Literal(Literal::Int(5))),
+      vec![node(Stmt::Expr(node(Expr::Infix(
+        Box::new(node(Expr::Literal(Literal::Int(5)))),
         Infix::Divide,
-        Box::new(Expr::Literal(Literal::Int(5))),
-      ))],
+        Box::new(node(Expr::Literal(Literal::Int(5)))),
+      ))))],
     ),
     (
       "5 > 5",
-      vec![Stmt::Expr(Expr::Infix(
-        Box::new(Expr::Literal(Literal::Int(5))),
+      vec![node(Stmt::Expr(node(Expr::Infix(
+        Box::new(node(Expr::Literal(Literal::Int(5)))),
         Infix::GreaterThan,
-        Box::new(Expr::Literal(Literal::Int(5))),
-      ))],
+        Box::new(node(Expr::Literal(Literal::Int(5)))),
+      ))))],
     ),
     (
       "5 < 5",
-      vec![Stmt::Expr(Expr::Infix(
-        Box::new(Expr::Literal(Literal::Int(5))),
+      vec![node(Stmt::Expr(node(Expr::Infix(
+        Box::new(node(Expr::Literal(Literal::Int(5)))),
         Infix::LessThan,
-        Box::new(Expr::Literal(Literal::Int(5))),
-      ))],
+        Box::new(node(Expr::Literal(Literal::Int(5)))),
+      ))))],
     ),
     (
       "5 == 5",
-      vec![Stmt::Expr(Expr::Infix(
-        Box::new(Expr::Literal(Literal::Int(5))),
+      vec![node(Stmt::Expr(node(Expr::Infix(
+        Box::new(node(Expr::Literal(Literal::Int(5)))),
         Infix::Equal,
-        Box::new(Expr::Literal(Literal::Int(5))),
-      ))],
+        Box::new(node(Expr::Literal(Literal::Int(5)))),
+      ))))],
     ),
     (
       "5 != 5",
-      vec![Stmt::Expr(Expr::Infix(
-        Box::new(Expr::Literal(Literal::Int(5))),
+      vec![node(Stmt::Expr(node(Expr::Infix(
+        Box::new(node(Expr::Literal(Literal::Int(5)))),
         Infix::NotEqual,
-        Box::new(Expr::Literal(Literal::Int(5))),
-      ))],
+        Box::new(node(Expr::Literal(Literal::Int(5)))),
+      ))))],
     ),
   ];
 
@@ -235,290 +354,290 @@ fn operator_precedence_parsing() {
   let cases = vec![
     (
       "3 + 4 * 5 == 3 * 1 + 4 * 5",
-      vec![Stmt::Expr(Expr::Infix(
-        Box::new(Expr::Infix(
-          Box::new(Expr::Literal(Literal::Int(3))),
+      vec![node(Stmt::Expr(node(Expr::Infix(
+        Box::new(node(Expr::Infix(
+          Box::new(node(Expr::Literal(Literal::Int(3)))),
           Infix::Plus,
-          Box::new(Expr::Infix(
-            Box::new(Expr::Literal(Literal::Int(4))),
+          Box::new(node(Expr::Infix(
+            Box::new(node(Expr::Literal(Literal::Int(4)))),
             Infix::Multiply,
-            Box::new(Expr::Literal(Literal::Int(5))),
-          )),
-        )),
+            Box::new(node(Expr::Literal(Literal::Int(5)))),
+          ))),
+        ))),
         Infix::Equal,
-        Box::new(Expr::Infix(
-          Box::new(Expr::Infix(
-            Box::new(Expr::Literal(Literal::Int(3))),
+        Box::new(node(Expr::Infix(
+          Box::new(node(Expr::Infix(
+            Box::new(node(Expr::Literal(Literal::Int(3)))),
             Infix::Multiply,
-            Box::new(Expr::Literal(Literal::Int(1))),
-          )),
+            Box::new(node(Expr::Literal(Literal::Int(1)))),
+          ))),
           Infix::Plus,
-          Box::new(Expr::Infix(
-            Box::new(Expr::Literal(Literal::Int(4))),
+          Box::new(node(Expr::Infix(
+            Box::new(node(Expr::Literal(Literal::Int(4)))),
             Infix::Multiply,
-            Box::new(Expr::Literal(Literal::Int(5))),
-          )),
-        )),
-      ))],
+            Box::new(node(Expr::Literal(Literal::Int(5)))),
+          ))),
+        ))),
+      ))))],
     ),
     (
       "3 + 4; -5 * 5",
       vec![
-        Stmt::Expr(Expr::Infix(
-          Box::new(Expr::Literal(Literal::Int(3))),
+        node(Stmt::Expr(node(Expr::Infix(
+          Box::new(node(Expr::Literal(Literal::Int(3)))),
           Infix::Plus,
-          Box::new(Expr::Literal(Literal::Int(4))),
-        )),
-        Stmt::Expr(Expr::Infix(
-          Box::new(Expr::Prefix(
+          Box::new(node(Expr::Literal(Literal::Int(4)))),
+        )))),
+        node(Stmt::Expr(node(Expr::Infix(
+          Box::new(node(Expr::Prefix(
             Prefix::Minus,
-            Box::new(Expr::Literal(Literal::Int(5))),
-          )),
+            Box::new(node(Expr::Literal(Literal::Int(5)))),
+          ))),
           Infix::Multiply,
-          Box::new(Expr::Literal(Literal::Int(5))),
-        )),
+          Box::new(node(Expr::Literal(Literal::Int(5)))),
+        )))),
       ],
     ),
     (
       "5 > 4 == 3 < 4",
-      vec![Stmt::Expr(Expr::Infix(
-        Box::new(Expr::Infix(
-          Box::new(Expr::Literal(Literal::Int(5))),
+      vec![node(Stmt::Expr(node(Expr::Infix(
+        Box::new(node(Expr::Infix(
+          Box::new(node(Expr::Literal(Literal::Int(5)))),
           Infix::GreaterThan,
-          Box::new(Expr::Literal(Literal::Int(4))),
-        )),
+          Box::new(node(Expr::Literal(Literal::Int(4)))),
+        ))),
         Infix::Equal,
-        Box::new(Expr::Infix(
-          Box::new(Expr::Literal(Literal::Int(3))),
+        Box::new(node(Expr::Infix(
+          Box::new(node(Expr::Literal(Literal::Int(3)))),
           Infix::LessThan,
-          Box::new(Expr::Literal(Literal::Int(4))),
-        )),
-      ))],
+          Box::new(node(Expr::Literal(Literal::Int(4)))),
+        ))),
+      ))))],
     ),
     (
       "5 < 4 != 3 > 4",
-      vec![Stmt::Expr(Expr::Infix(
-        Box::new(Expr::Infix(
-          Box::new(Expr::Literal(Literal::Int(5))),
+      vec![node(Stmt::Expr(node(Expr::Infix(
+        Box::new(node(Expr::Infix(
+          Box::new(node(Expr::Literal(Literal::Int(5)))),
           Infix::LessThan,
-          Box::new(Expr::Literal(Literal::Int(4))),
-        )),
+          Box::new(node(Expr::Literal(Literal::Int(4)))),
+        ))),
         Infix::NotEqual,
-        Box::new(Expr::Infix(
-          Box::new(Expr::Literal(Literal::Int(3))),
+        Box::new(node(Expr::Infix(
+          Box::new(node(Expr::Literal(Literal::Int(3)))),
           Infix::GreaterThan,
-          Box::new(Expr::Literal(Literal::Int(4))),
-        )),
-      ))],
+          Box::new(node(Expr::Literal(Literal::Int(4)))),
+        ))),
+      ))))],
     ),
     (
       "-a * b",
-      vec![Stmt::Expr(Expr::Infix(
-        Box::new(Expr::Prefix(
+      vec![node(Stmt::Expr(node(Expr::Infix(
+        Box::new(node(Expr::Prefix(
           Prefix::Minus,
-          Box::new(Expr::Ident(Ident("a"))),
-        )),
+          Box::new(node(Expr::Ident(Ident::from("a")))),
+        ))),
         Infix::Multiply,
-        Box::new(Expr::Ident(Ident("b"))),
-      ))],
+        Box::new(node(Expr::Ident(Ident::from("b")))),
+      ))))],
     ),
     (
       "!-a",
-      vec![Stmt::Expr(Expr::Prefix(
+      vec![node(Stmt::Expr(node(Expr::Prefix(
         Prefix::Bang,
-        Box::new(Expr::Prefix(
+        Box::new(node(Expr::Prefix(
           Prefix::Minus,
-          Box::new(Expr::Ident(Ident("a"))),
-        )),
-      ))],
+          Box::new(node(Expr::Ident(Ident::from("a")))),
+        ))),
+      ))))],
     ),
     (
       "a + b - c",
-      vec![Stmt::Expr(Expr::Infix(
-        Box::new(Expr::Infix(
-          Box::new(Expr::Ident(Ident("a"))),
+      vec![node(Stmt::Expr(node(Expr::Infix(
+        Box::new(node(Expr::Infix(
+          Box::new(node(Expr::Ident(Ident::from("a")))),
           Infix::Plus,
-          Box::new(Expr::Ident(Ident("b"))),
-        )),
+          Box::new(node(Expr::Ident(Ident::from("b")))),
+        ))),
         Infix::Minus,
-        Box::new(Expr::Ident(Ident("c"))),
-      ))],
+        Box::new(node(Expr::Ident(Ident::from("c")))),
+      ))))],
     ),
     (
       "a + b * c + d / e - f",
-      vec![Stmt::Expr(Expr::Infix(
-        Box::new(Expr::Infix(
-          Box::new(Expr::Infix(
-            Box::new(Expr::Ident(Ident("a"))),
+      vec![node(Stmt::Expr(node(Expr::Infix(
+        Box::new(node(Expr::Infix(
+          Box::new(node(Expr::Infix(
+            Box::new(node(Expr::Ident(Ident::from("a")))),
             Infix::Plus,
-            Box::new(Expr::Infix(
-              Box::new(Expr::Ident(Ident("b"))),
+            Box::new(node(Expr::Infix(
+              Box::new(node(Expr::Ident(Ident::from("b")))),
               Infix::Multiply,
-              Box::new(Expr::Ident(Ident("c"))),
-            )),
-          )),
+              Box::new(node(Expr::Ident(Ident::from("c")))),
+            ))),
+          ))),
           Infix::Plus,
-          Box::new(Expr::Infix(
-            Box::new(Expr::Ident(Ident("d"))),
+          Box::new(node(Expr::Infix(
+            Box::new(node(Expr::Ident(Ident::from("d")))),
             Infix::Divide,
-            Box::new(Expr::Ident(Ident("e"))),
-          )),
-        )),
+            Box::new(node(Expr::Ident(Ident::from("e")))),
+          ))),
+        ))),
         Infix::Minus,
-        Box::new(Expr::Ident(Ident("f"))),
-      ))],
+        Box::new(node(Expr::Ident(Ident::from("f")))),
+      ))))],
     ),
     (
       "1 + (2 + 3) + 4",
-      vec![Stmt::Expr(Expr::Infix(
-        Box::new(Expr::Infix(
-          Box::new(Expr::Literal(Literal::Int(1))),
+      vec![node(Stmt::Expr(node(Expr::Infix(
+        Box::new(node(Expr::Infix(
+          Box::new(node(Expr::Literal(Literal::Int(1)))),
           Infix::Plus,
-          Box::new(Expr::Infix(
-            Box::new(Expr::Literal(Literal::Int(2))),
+          Box::new(node(Expr::Infix(
+            Box::new(node(Expr::Literal(Literal::Int(2)))),
             Infix::Plus,
-            Box::new(Expr::Literal(Literal::Int(3))),
-          )),
-        )),
+            Box::new(node(Expr::Literal(Literal::Int(3)))),
+          ))),
+        ))),
         Infix::Plus,
-        Box::new(Expr::Literal(Literal::Int(4))),
-      ))],
+        Box::new(node(Expr::Literal(Literal::Int(4)))),
+      ))))],
     ),
     (
       "(5 + 5) * 2",
-      vec![Stmt::Expr(Expr::Infix(
-        Box::new(Expr::Infix(
-          Box::new(Expr::Literal(Literal::Int(5))),
+      vec![node(Stmt::Expr(node(Expr::Infix(
+        Box::new(node(Expr::Infix(
+          Box::new(node(Expr::Literal(Literal::Int(5)))),
           Infix::Plus,
-          Box::new(Expr::Literal(Literal::Int(5))),
-        )),
+          Box::new(node(Expr::Literal(Literal::Int(5)))),
+        ))),
         Infix::Multiply,
-        Box::new(Expr::Literal(Literal::Int(2))),
-      ))],
+        Box::new(node(Expr::Literal(Literal::Int(2)))),
+      ))))],
     ),
     (
       "2 / (5 + 5)",
-      vec![Stmt::Expr(Expr::Infix(
-        Box::new(Expr::Literal(Literal::Int(2))),
+      vec![node(Stmt::Expr(node(Expr::Infix(
+        Box::new(node(Expr::Literal(Literal::Int(2)))),
         Infix::Divide,
-        Box::new(Expr::Infix(
-          Box::new(Expr::Literal(Literal::Int(5))),
+        Box::new(node(Expr::Infix(
+          Box::new(node(Expr::Literal(Literal::Int(5)))),
           Infix::Plus,
-          Box::new(Expr::Literal(Literal::Int(5))),
-        )),
-      ))],
+          Box::new(node(Expr::Literal(Literal::Int(5)))),
+        ))),
+      ))))],
     ),
     (
       "-(5 + 5)",
-      vec![Stmt::Expr(Expr::Prefix(
+      vec![node(Stmt::Expr(node(Expr::Prefix(
         Prefix::Minus,
-        Box::new(Expr::Infix(
-          Box::new(Expr::Literal(Literal::Int(5))),
+        Box::new(node(Expr::Infix(
+          Box::new(node(Expr::Literal(Literal::Int(5)))),
           Infix::Plus,
-          Box::new(Expr::Literal(Literal::Int(5))),
-        )),
-      ))],
+          Box::new(node(Expr::Literal(Literal::Int(5)))),
+        ))),
+      ))))],
     ),
     (
       "!(true == true)",
-      vec![Stmt::Expr(Expr::Prefix(
+      vec![node(Stmt::Expr(node(Expr::Prefix(
         Prefix::Bang,
-        Box::new(Expr::Infix(
-          Box::new(Expr::Literal(Literal::Bool(true))),
+        Box::new(node(Expr::Infix(
+          Box::new(node(Expr::Literal(Literal::Bool(true)))),
           Infix::Equal,
-          Box::new(Expr::Literal(Literal::Bool(true))),
-        )),
-      ))],
+          Box::new(node(Expr::Literal(Literal::Bool(true)))),
+        ))),
+      ))))],
     ),
     (
       "(1 < 2) == true",
-      vec![Stmt::Expr(Expr::Infix(
-        Box::new(Expr::Infix(
-          Box::new(Expr::Literal(Literal::Int(1))),
+      vec![node(Stmt::Expr(node(Expr::Infix(
+        Box::new(node(Expr::Infix(
+          Box::new(node(Expr::Literal(Literal::Int(1)))),
           Infix::LessThan,
-          Box::new(Expr::Literal(Literal::Int(2))),
-        )),
+          Box::new(node(Expr::Literal(Literal::Int(2)))),
+        ))),
         Infix::Equal,
-        Box::new(Expr::Literal(Literal::Bool(true))),
-      ))],
+        Box::new(node(Expr::Literal(Literal::Bool(true)))),
+      ))))],
     ),
     (
       "a + add(b * c) + d",
-      vec![Stmt::Expr(Expr::Infix(
-        Box::new(Expr::Infix(
-          Box::new(Expr::Ident(Ident("a"))),
+      vec![node(Stmt::Expr(node(Expr::Infix(
+        Box::new(node(Expr::Infix(
+          Box::new(node(Expr::Ident(Ident::from("a")))),
           Infix::Plus,
-          Box::new(Expr::Call {
-            function: Box::new(Expr::Ident(Ident("add"))),
-            arguments: vec![Expr::Infix(
-              Box::new(Expr::Ident(Ident("b"))),
+          Box::new(node(Expr::Call {
+            function: Box::new(node(Expr::Ident(Ident::from("add")))),
+            arguments: vec![node(Expr::Infix(
+              Box::new(node(Expr::Ident(Ident::from("b")))),
               Infix::Multiply,
-              Box::new(Expr::Ident(Ident("c"))),
-            )],
-          }),
-        )),
+              Box::new(node(Expr::Ident(Ident::from("c")))),
+            ))],
+          })),
+        ))),
         Infix::Plus,
-        Box::new(Expr::Ident(Ident("d"))),
-      ))],
+        Box::new(node(Expr::Ident(Ident::from("d")))),
+      ))))],
     ),
     (
       "add(a, b, 1, 2 * 3, 4 + 5, add(6, 7 * 8))",
-      vec![Stmt::Expr(Expr::Call {
-        function: Box::new(Expr::Ident(Ident("add"))),
+      vec![node(Stmt::Expr(node(Expr::Call {
+        function: Box::new(node(Expr::Ident(Ident::from("add")))),
         arguments: vec![
-          Expr::Ident(Ident("a")),
-          Expr::Ident(Ident("b")),
-          Expr::Literal(Literal::Int(1)),
-          Expr::Infix(
-            Box::new(Expr::Literal(Literal::Int(2))),
+          node(Expr::Ident(Ident::from("a"))),
+          node(Expr::Ident(Ident::from("b"))),
+          node(Expr::Literal(Literal::Int(1))),
+          node(Expr::Infix(
+            Box::new(node(Expr::Literal(Literal::Int(2)))),
             Infix::Multiply,
-            Box::new(Expr::Literal(Literal::Int(3))),
-          ),
-          Expr::Infix(
-            Box::new(Expr::Literal(Literal::Int(4))),
+            Box::new(node(Expr::Literal(Literal::Int(3)))),
+          )),
+          node(Expr::Infix(
+            Box::new(node(Expr::Literal(Literal::Int(4)))),
             Infix::Plus,
-            Box::new(Expr::Literal(Literal::Int(5))),
-          ),
-          Expr::Call {
-            function: Box::new(Expr::Ident(Ident("add"))),
+            Box::new(node(Expr::Literal(Literal::Int(5)))),
+          )),
+          node(Expr::Call {
+            function: Box::new(node(Expr::Ident(Ident::from("add")))),
             arguments: vec![
-              Expr::Literal(Literal::Int(6)),
-              Expr::Infix(
-                Box::new(Expr::Literal(Literal::Int(7))),
+              node(Expr::Literal(Literal::Int(6))),
+              node(Expr::Infix(
+                Box::new(node(Expr::Literal(Literal::Int(7)))),
                 Infix::Multiply,
-                Box::new(Expr::Literal(Literal::Int(8))),
-              ),
+                Box::new(node(Expr::Literal(Literal::Int(8)))),
+              )),
             ],
-          },
+          }),
         ],
-      })],
+      })))],
     ),
     (
       "add(a + b + c * d / f + g)",
-      vec![Stmt::Expr(Expr::Call {
-        function: Box::new(Expr::Ident(Ident("add"))),
-        arguments: vec![Expr::Infix(
-          Box::new(Expr::Infix(
-            Box::new(Expr::Infix(
-              Box::new(Expr::Ident(Ident("a"))),
+      vec![node(Stmt::Expr(node(Expr::Call {
+        function: Box::new(node(Expr::Ident(Ident::from("add")))),
+        arguments: vec![node(Expr::Infix(
+          Box::new(node(Expr::Infix(
+            Box::new(node(Expr::Infix(
+              Box::new(node(Expr::Ident(Ident::from("a")))),
               Infix::Plus,
-              Box::new(Expr::Ident(Ident("b"))),
-            )),
+              Box::new(node(Expr::Ident(Ident::from("b")))),
+            ))),
             Infix::Plus,
-            Box::new(Expr::Infix(
-              Box::new(Expr::Infix(
-                Box::new(Expr::Ident(Ident("c"))),
+            Box::new(node(Expr::Infix(
+              Box::new(node(Expr::Infix(
+                Box::new(node(Expr::Ident(Ident::from("c")))),
                 Infix::Multiply,
-                Box::new(Expr::Ident(Ident("d"))),
-              )),
+                Box::new(node(Expr::Ident(Ident::from("d")))),
+              ))),
               Infix::Divide,
-              Box::new(Expr::Ident(Ident("f"))),
-            )),
-          )),
+              Box::new(node(Expr::Ident(Ident::from("f")))),
+            ))),
+          ))),
           Infix::Plus,
-          Box::new(Expr::Ident(Ident("g"))),
-        )],
-      })],
+          Box::new(node(Expr::Ident(Ident::from("g")))),
+        ))],
+      })))],
     ),
   ];
 
@@ -529,32 +648,206 @@ fn operator_precedence_parsing() {
   }
 }
 
+#[test]
+fn comparison_precedence() {
+  let parser = parser!("a + 1 <= b == c >= d * 2");
+
+  assert_eq!(
+    parser.stmts,
+    vec![node(Stmt::Expr(node(Expr::Infix(
+      Box::new(node(Expr::Infix(
+        Box::new(node(Expr::Infix(
+          Box::new(node(Expr::Ident(Ident::from("a")))),
+          Infix::Plus,
+          Box::new(node(Expr::Literal(Literal::Int(1)))),
+        ))),
+        Infix::LessThanEqual,
+        Box::new(node(Expr::Ident(Ident::from("b")))),
+      ))),
+      Infix::Equal,
+      Box::new(node(Expr::Infix(
+        Box::new(node(Expr::Ident(Ident::from("c")))),
+        Infix::GreaterThanEqual,
+        Box::new(node(Expr::Infix(
+          Box::new(node(Expr::Ident(Ident::from("d")))),
+          Infix::Multiply,
+          Box::new(node(Expr::Literal(Literal::Int(2)))),
+        ))),
+      ))),
+    ))))]
+  );
+}
+
+#[test]
+fn logical_precedence() {
+  let cases = vec![
+    ("a || b && c", "a || (b && c)"),
+    ("a && b || c", "(a && b) || c"),
+    ("a || b || c", "(a || b) || c"),
+    ("a == 1 && b < 2 || !c", "((a == 1) && (b < 2)) || (!c)"),
+  ];
+
+  for (input, grouped) in cases {
+    assert_eq!(parser!(input).stmts, parser!(grouped).stmts, "{}", input);
+  }
+
+  let parser = parser!("a && b");
+  assert_eq!(
+    parser.stmts,
+    vec![node(Stmt::Expr(node(Expr::Infix(
+      Box::new(node(Expr::Ident(Ident::from("a")))),
+      Infix::And,
+      Box::new(node(Expr::Ident(Ident::from("b")))),
+    ))))]
+  );
+}
+
+#[test]
+fn range_precedence() {
+  let cases = vec![
+    ("0..n + 1", "0..(n + 1)"),
+    ("a || b..c && d", "(a || b)..(c && d)"),
+    ("-1..=2 * 3", "(-1)..=(2 * 3)"),
+    ("a..b..c", "(a..b)..c"),
+  ];
+
+  for (input, grouped) in cases {
+    assert_eq!(parser!(input).stmts, parser!(grouped).stmts, "{}", input);
+  }
+}
+
+#[test]
+fn assignment_associativity() {
+  let cases = vec![
+    ("a = b = c", "a = (b = c)"),
+    ("a += b -= 1", "a += (b -= 1)"),
+    ("a = b || c", "a = (b || c)"),
+    ("a = 0..n", "a = (0..n)"),
+    ("a[i + 1] **= 2 * 3", "a[i + 1] **= (2 * 3)"),
+  ];
+
+  for (input, grouped) in cases {
+    assert_eq!(parser!(input).stmts, parser!(grouped).stmts, "{}", input);
+  }
+
+  let parser = parser!("a[0] <<= b = 1");
+  assert_eq!(
+    parser.stmts,
+    vec![node(Stmt::Expr(node(Expr::Assign {
+      target: Box::new(node(Expr::Index {
+        left: Box::new(node(Expr::Ident(Ident::from("a")))),
+        index: Box::new(node(Expr::Literal(Literal::Int(0)))),
+      })),
+      operator: Some(Infix::ShiftLeft),
+      value: Box::new(node(Expr::Assign {
+        target: Box::new(node(Expr::Ident(Ident::from("b")))),
+        operator: None,
+        value: Box::new(node(Expr::Literal(Literal::Int(1)))),
+      })),
+    })))]
+  );
+}
+
+#[test]
+fn power_associativity() {
+  let cases = vec![
+    ("a ** b ** c", "a ** (b ** c)"),
+    ("-a ** b", "-(a ** b)"),
+    ("a ** -b ** c", "a ** (-(b ** c))"),
+    ("a * b ** c * d", "(a * (b ** c)) * d"),
+    ("a ** b[c] ** f(d)", "a ** ((b[c]) ** (f(d)))"),
+    ("a - b - c", "(a - b) - c"),
+  ];
+
+  for (input, grouped) in cases {
+    assert_eq!(parser!(input).stmts, parser!(grouped).stmts, "{}", input);
+  }
+
+  let parser = parser!("2 ** 3 ** 2");
+  assert_eq!(
+    parser.stmts,
+    vec![node(Stmt::Expr(node(Expr::Infix(
+      Box::new(node(Expr::Literal(Literal::Int(2)))),
+      Infix::Power,
+      Box::new(node(Expr::Infix(
+        Box::new(node(Expr::Literal(Literal::Int(3)))),
+        Infix::Power,
+        Box::new(node(Expr::Literal(Literal::Int(2)))),
+      ))),
+    ))))]
+  );
+}
+
+#[test]
+fn bitwise_precedence() {
+  let cases = vec![
+    ("a | b ^ c & d", "a | (b ^ (c & d))"),
+    ("a & b == c", "a & (b == c)"),
+    ("a << 1 < b >> 2", "(a << 1) < (b >> 2)"),
+    ("a << b + c", "a << (b + c)"),
+    ("a + b % c * d", "a + ((b % c) * d)"),
+    ("a || b | c && d", "a || ((b | c) && d)"),
+    ("~a & ~-b", "(~a) & (~(-b))"),
+  ];
+
+  for (input, grouped) in cases {
+    assert_eq!(parser!(input).stmts, parser!(grouped).stmts, "{}", input);
+  }
+
+  let parser = parser!("~a % 2");
+  assert_eq!(
+    parser.stmts,
+    vec![node(Stmt::Expr(node(Expr::Infix(
+      Box::new(node(Expr::Prefix(
+        Prefix::BitNot,
+        Box::new(node(Expr::Ident(Ident::from("a")))),
+      ))),
+      Infix::Modulo,
+      Box::new(node(Expr::Literal(Literal::Int(2)))),
+    ))))]
+  );
+}
+
+#[test]
+fn unicode_operators() {
+  let cases = vec![
+    ("a × b ÷ c", "a * b / c"),
+    ("a ≠ b", "a != b"),
+    ("a ≤ b ≥ c", "a <= b >= c"),
+    ("1 + 2 × 3 ≤ 4 ≠ true", "1 + 2 * 3 <= 4 != true"),
+  ];
+
+  for (unicode, ascii) in cases {
+    assert_eq!(parser!(unicode).stmts, parser!(ascii).stmts, "{}", unicode);
+  }
+}
+
 #[test]
 fn if_expr() {
   let cases = vec![
     (
       "if (x < y) { x }",
-      vec![Stmt::Expr(Expr::If {
-        condition: Box::new(Expr::Infix(
-          Box::new(Expr::Ident(Ident("x"))),
+      vec![node(Stmt::Expr(node(Expr::If {
+        condition: Box::new(node(Expr::Infix(
+          Box::new(node(Expr::Ident(Ident::from("x")))),
           Infix::LessThan,
-          Box::new(Expr::Ident(Ident("y"))),
-        )),
-        consequence: vec![Stmt::Expr(Expr::Ident(Ident("x")))],
+          Box::new(node(Expr::Ident(Ident::from("y")))),
+        ))),
+        consequence: vec![node(Stmt::Expr(node(Expr::Ident(Ident::from("x")))))],
         alternative: None,
-      })],
+      })))],
     ),
     (
       "if (x < y) { x } else { y }",
-      vec![Stmt::Expr(Expr::If {
-        condition: Box::new(Expr::Infix(
-          Box::new(Expr::Ident(Ident("x"))),
+      vec![node(Stmt::Expr(node(Expr::If {
+        condition: Box::new(node(Expr::Infix(
+          Box::new(node(Expr::Ident(Ident::from("x")))),
           Infix::LessThan,
-          Box::new(Expr::Ident(Ident("y"))),
-        )),
-        consequence: vec![Stmt::Expr(Expr::Ident(Ident("x")))],
-        alternative: Some(vec![Stmt::Expr(Expr::Ident(Ident("y")))]),
-      })],
+          Box::new(node(Expr::Ident(Ident::from("y")))),
+        ))),
+        consequence: vec![node(Stmt::Expr(node(Expr::Ident(Ident::from("x")))))],
+        alternative: Some(vec![node(Stmt::Expr(node(Expr::Ident(Ident::from("y")))))]),
+      })))],
     ),
   ];
 
@@ -565,18 +858,145 @@ fn if_expr() {
   }
 }
 
+#[test]
+fn while_expr() {
+  let parser = parser!("while (x < y) { if (x) { break } continue; x }");
+
+  assert_eq!(
+    parser.stmts,
+    vec![node(Stmt::Expr(node(Expr::While {
+      condition: Box::new(node(Expr::Infix(
+        Box::new(node(Expr::Ident(Ident::from("x")))),
+        Infix::LessThan,
+        Box::new(node(Expr::Ident(Ident::from("y")))),
+      ))),
+      body: vec![
+        node(Stmt::Expr(node(Expr::If {
+          condition: Box::new(node(Expr::Ident(Ident::from("x")))),
+          consequence: vec![node(Stmt::Break)],
+          alternative: None,
+        }))),
+        node(Stmt::Continue),
+        node(Stmt::Expr(node(Expr::Ident(Ident::from("x"))))),
+      ],
+    })))]
+  )
+}
+
+#[test]
+fn for_expr() {
+  let cases = vec![
+    (
+      "for (x in xs) { x }",
+      ForBinding::Item(node(Ident::from("x"))),
+      Expr::Ident(Ident::from("xs")),
+    ),
+    (
+      "for (i, x in 0..n) { x }",
+      ForBinding::Entry(node(Ident::from("i")), node(Ident::from("x"))),
+      Expr::Infix(
+        Box::new(node(Expr::Literal(Literal::Int(0)))),
+        Infix::Range,
+        Box::new(node(Expr::Ident(Ident::from("n")))),
+      ),
+    ),
+  ];
+
+  for (input, binding, iterable) in cases {
+    let parser = parser!(input);
+    assert_eq!(
+      parser.stmts,
+      vec![node(Stmt::Expr(node(Expr::For {
+        binding,
+        iterable: Box::new(node(iterable)),
+        body: vec![node(Stmt::Expr(node(Expr::Ident(Ident::from("x")))))],
+      })))]
+    );
+  }
+}
+
+#[test]
+fn match_expr() {
+  let parser = parser!(
+    r#"match (x) {
+      0 => a,
+      -1.5 if a => b,
+      [_, y, ..rest] => y,
+      {"k": true, 1: [..]} => c,
+      z => { let w = z; w }
+    }"#
+  );
+  let ident = |name: &str| node(Expr::Ident(Ident::from(name)));
+  let arm = |pattern, guard, body| MatchArm {
+    pattern: node(pattern),
+    guard,
+    body: vec![node(Stmt::Expr(ident(body)))],
+  };
+
+  assert_eq!(
+    parser.stmts,
+    vec![node(Stmt::Expr(node(Expr::Match {
+      subject: Box::new(ident("x")),
+      arms: vec![
+        arm(Pattern::Literal(Literal::Int(0)), None, "a"),
+        arm(
+          Pattern::Literal(Literal::Float(-1.5)),
+          Some(ident("a")),
+          "b"
+        ),
+        arm(
+          Pattern::Array {
+            elements: vec![
+              node(Pattern::Wildcard),
+              node(Pattern::Binding(Ident::from("y")))
+            ],
+            rest: Some(Box::new(node(Pattern::Binding(Ident::from("rest"))))),
+          },
+          None,
+          "y",
+        ),
+        arm(
+          Pattern::Hash(vec![
+            (
+              node(Literal::Str("k".to_string())),
+              node(Pattern::Literal(Literal::Bool(true)))
+            ),
+            (
+              node(Literal::Int(1)),
+              node(Pattern::Array {
+                elements: vec![],
+                rest: Some(Box::new(node(Pattern::Wildcard))),
+              }),
+            ),
+          ]),
+          None,
+          "c",
+        ),
+        MatchArm {
+          pattern: node(Pattern::Binding(Ident::from("z"))),
+          guard: None,
+          body: vec![
+            node(Stmt::Let(node(Ident::from("w")), ident("z"))),
+            node(Stmt::Expr(ident("w"))),
+          ],
+        },
+      ],
+    })))]
+  );
+}
+
 #[test]
 fn function_literal() {
   let cases = vec![(
     "fn (x, y) { x + y; }",
-    vec![Stmt::Expr(Expr::Function {
-      params: vec![Ident("x"), Ident("y")],
-      body: vec![Stmt::Expr(Expr::Infix(
-        Box::new(Expr::Ident(Ident("x"))),
+    vec![node(Stmt::Expr(node(Expr::Function {
+      params: vec![node(Ident::from("x")), node(Ident::from("y"))],
+      body: vec![node(Stmt::Expr(node(Expr::Infix(
+        Box::new(node(Expr::Ident(Ident::from("x")))),
         Infix::Plus,
-        Box::new(Expr::Ident(Ident("y"))),
-      ))],
-    })],
+        Box::new(node(Expr::Ident(Ident::from("y")))),
+      ))))],
+    })))],
   )];
 
   for (input, expected) in cases {
@@ -590,21 +1010,676 @@ fn call_expr() {
   let parser = parser!("add(1, 2 * 3, 4 + 5)");
   assert_eq!(
     parser.stmts,
-    vec![Stmt::Expr(Expr::Call {
-      function: Box::new(Expr::Ident(Ident("add"))),
+    vec![node(Stmt::Expr(node(Expr::Call {
+      function: Box::new(node(Expr::Ident(Ident::from("add")))),
       arguments: vec![
-        Expr::Literal(Literal::Int(1)),
-        Expr::Infix(
-          Box::new(Expr::Literal(Literal::Int(2))),
+        node(Expr::Literal(Literal::Int(1))),
+        node(Expr::Infix(
+          Box::new(node(Expr::Literal(Literal::Int(2)))),
           Infix::Multiply,
-          Box::new(Expr::Literal(Literal::Int(3)))
-        ),
-        Expr::Infix(
-          Box::new(Expr::Literal(Literal::Int(4))),
+          Box::new(node(Expr::Literal(Literal::Int(3))))
+        )),
+        node(Expr::Infix(
+          Box::new(node(Expr::Literal(Literal::Int(4)))),
           Infix::Plus,
-          Box::new(Expr::Literal(Literal::Int(5)))
-        )
+          Box::new(node(Expr::Literal(Literal::Int(5))))
+        ))
       ]
-    })]
+    })))]
   )
 }
+
+#[test]
+fn array_literal() {
+  let cases = vec![
+    ("[]", vec![node(Stmt::Expr(node(Expr::Array(vec![]))))]),
+    (
+      "[1, 2 * 3, \"a\"]",
+      vec![node(Stmt::Expr(node(Expr::Array(vec![
+        node(Expr::Literal(Literal::Int(1))),
+        node(Expr::Infix(
+          Box::new(node(Expr::Literal(Literal::Int(2)))),
+          Infix::Multiply,
+          Box::new(node(Expr::Literal(Literal::Int(3)))),
+        )),
+        node(Expr::Literal(Literal::Str("a".to_string()))),
+      ]))))],
+    ),
+  ];
+
+  for (input, expected) in cases {
+    let parser = parser!(input);
+
+    assert_eq!(parser.stmts, expected);
+  }
+}
+
+#[test]
+fn hash_literal() {
+  let cases = vec![
+    ("{}", vec![node(Stmt::Expr(node(Expr::Hash(vec![]))))]),
+    (
+      r#"{"a": 1, 2: true, x: 1 + 1}"#,
+      vec![node(Stmt::Expr(node(Expr::Hash(vec![
+        (
+          node(Expr::Literal(Literal::Str("a".to_string()))),
+          node(Expr::Literal(Literal::Int(1))),
+        ),
+        (
+          node(Expr::Literal(Literal::Int(2))),
+          node(Expr::Literal(Literal::Bool(true))),
+        ),
+        (
+          node(Expr::Ident(Ident::from("x"))),
+          node(Expr::Infix(
+            Box::new(node(Expr::Literal(Literal::Int(1)))),
+            Infix::Plus,
+            Box::new(node(Expr::Literal(Literal::Int(1)))),
+          )),
+        ),
+      ]))))],
+    ),
+    (
+      r#"if (x) { {"a": 1}["a"] }"#,
+      vec![node(Stmt::Expr(node(Expr::If {
+        condition: Box::new(node(Expr::Ident(Ident::from("x")))),
+        consequence: vec![node(Stmt::Expr(node(Expr::Index {
+          left: Box::new(node(Expr::Hash(vec![(
+            node(Expr::Literal(Literal::Str("a".to_string()))),
+            node(Expr::Literal(Literal::Int(1))),
+          )]))),
+          index: Box::new(node(Expr::Literal(Literal::Str("a".to_string())))),
+        })))],
+        alternative: None,
+      })))],
+    ),
+  ];
+
+  for (input, expected) in cases {
+    let parser = parser!(input);
+
+    assert_eq!(parser.stmts, expected);
+  }
+}
+
+#[test]
+fn index_expr() {
+  let cases = vec![
+    (
+      "arr[1 + 1]",
+      vec![node(Stmt::Expr(node(Expr::Index {
+        left: Box::new(node(Expr::Ident(Ident::from("arr")))),
+        index: Box::new(node(Expr::Infix(
+          Box::new(node(Expr::Literal(Literal::Int(1)))),
+          Infix::Plus,
+          Box::new(node(Expr::Literal(Literal::Int(1)))),
+        ))),
+      })))],
+    ),
+    (
+      "a * [1, 2][b]",
+      vec![node(Stmt::Expr(node(Expr::Infix(
+        Box::new(node(Expr::Ident(Ident::from("a")))),
+        Infix::Multiply,
+        Box::new(node(Expr::Index {
+          left: Box::new(node(Expr::Array(vec![
+            node(Expr::Literal(Literal::Int(1))),
+            node(Expr::Literal(Literal::Int(2))),
+          ]))),
+          index: Box::new(node(Expr::Ident(Ident::from("b")))),
+        })),
+      ))))],
+    ),
+    (
+      "-a[0]",
+      vec![node(Stmt::Expr(node(Expr::Prefix(
+        Prefix::Minus,
+        Box::new(node(Expr::Index {
+          left: Box::new(node(Expr::Ident(Ident::from("a")))),
+          index: Box::new(node(Expr::Literal(Literal::Int(0)))),
+        })),
+      ))))],
+    ),
+    (
+      "f(x)[0][1]",
+      vec![node(Stmt::Expr(node(Expr::Index {
+        left: Box::new(node(Expr::Index {
+          left: Box::new(node(Expr::Call {
+            function: Box::new(node(Expr::Ident(Ident::from("f")))),
+            arguments: vec![node(Expr::Ident(Ident::from("x")))],
+          })),
+          index: Box::new(node(Expr::Literal(Literal::Int(0)))),
+        })),
+        index: Box::new(node(Expr::Literal(Literal::Int(1)))),
+      })))],
+    ),
+  ];
+
+  for (input, expected) in cases {
+    let parser = parser!(input);
+
+    assert_eq!(parser.stmts, expected);
+  }
+}
+
+#[test]
+fn node_spans() {
+  let parser = parser!("let x = -(1 + 22);\nadd(x, 3) * 2");
+  let stmts = &parser.stmts;
+
+  assert_eq!(stmts.len(), 2);
+  assert_eq!(stmts[0].span, Span::new(0, 18));
+  match &stmts[0].node {
+    Stmt::Let(name, value) => {
+      assert_eq!(name.span, Span::new(4, 5));
+      assert_eq!(value.span, Span::new(8, 17));
+      match &value.node {
+        Expr::Prefix(_, operand) => {
+          assert_eq!(operand.span, Span::new(9, 17));
+          match &operand.node {
+            Expr::Infix(left, _, right) => {
+              assert_eq!(left.span, Span::new(10, 11));
+              assert_eq!(right.span, Span::new(14, 16));
+            }
+            expr => panic!("unexpected expression {:?}", expr),
+          }
+        }
+        expr => panic!("unexpected expression {:?}", expr),
+      }
+    }
+    stmt => panic!("unexpected statement {:?}", stmt),
+  }
+
+  assert_eq!(stmts[1].span, Span::new(19, 32));
+  match &stmts[1].node {
+    Stmt::Expr(expr) => match &expr.node {
+      Expr::Infix(call, _, _) => {
+        assert_eq!(call.span, Span::new(19, 28));
+        match &call.node {
+          Expr::Call {
+            function,
+            arguments,
+          } => {
+            assert_eq!(function.span, Span::new(19, 22));
+            assert_eq!(arguments[0].span, Span::new(23, 24));
+            assert_eq!(arguments[1].span, Span::new(26, 27));
+          }
+          expr => panic!("unexpected expression {:?}", expr),
+        }
+      }
+      expr => panic!("unexpected expression {:?}", expr),
+    },
+    stmt => panic!("unexpected statement {:?}", stmt),
+  }
+}
+
+#[test]
+fn binding_spans() {
+  let parser = parser!("fn(a, bc) { for (k, v in h) { match (v) { {\"k\": x} => x } } }");
+  let (params, body) = match &parser.stmts[0].node {
+    Stmt::Expr(expr) => match &expr.node {
+      Expr::Function { params, body } => (params, body),
+      expr => panic!("unexpected expression {:?}", expr),
+    },
+    stmt => panic!("unexpected statement {:?}", stmt),
+  };
+  let spans = params.iter().map(|param| param.span).collect::<Vec<_>>();
+  assert_eq!(spans, vec![Span::new(3, 4), Span::new(6, 8)]);
+
+  let (binding, body) = match &body[0].node {
+    Stmt::Expr(expr) => match &expr.node {
+      Expr::For { binding, body, .. } => (binding, body),
+      expr => panic!("unexpected expression {:?}", expr),
+    },
+    stmt => panic!("unexpected statement {:?}", stmt),
+  };
+  match binding {
+    ForBinding::Entry(key, value) => {
+      assert_eq!(key.span, Span::new(17, 18));
+      assert_eq!(value.span, Span::new(20, 21));
+    }
+    binding => panic!("unexpected binding {:?}", binding),
+  }
+
+  match &body[0].node {
+    Stmt::Expr(expr) => match &expr.node {
+      Expr::Match { arms, .. } => match &arms[0].pattern.node {
+        Pattern::Hash(pairs) => assert_eq!(pairs[0].0.span, Span::new(43, 46)),
+        pattern => panic!("unexpected pattern {:?}", pattern),
+      },
+      expr => panic!("unexpected expression {:?}", expr),
+    },
+    stmt => panic!("unexpected statement {:?}", stmt),
+  }
+}
+
+#[test]
+fn error_spans() {
+  let mut parser = Parser::new(Lexer::new("let = 1;"));
+  let errors = parser.parse().get_errors();
+
+  assert_eq!(errors[0].span(), Span::new(4, 5));
+}
+
+#[test]
+fn render_errors() {
+  use crate::diagnostic::{Renderer, Source};
+
+  let input = "let = 1;";
+  let mut parser = Parser::new(Lexer::new(input));
+  let errors = parser.parse().get_errors();
+
+  assert_eq!(
+    Renderer::plain().render(&errors[0].to_diagnostic(), &Source::new("main.mk", input)),
+    "\
+error[E0001]: expected identifier, found `=`
+ --> main.mk:1:5
+  |
+1 | let = 1;
+  |     ^ expected identifier
+"
+  );
+}
+
+#[test]
+#[cfg(not(feature = "bigint"))]
+fn integer_overflow_note() {
+  use crate::diagnostic::NoteKind;
+
+  let mut parser = Parser::new(Lexer::new("-9223372036854775808"));
+  let errors = parser.parse().get_errors();
+
+  assert_eq!(errors[0].code(), "E0013");
+  assert_eq!(
+    errors[0].to_diagnostic().notes,
+    vec![(
+      NoteKind::Note,
+      "integer literals can be at most 9223372036854775807, a `-` in front of one is an operator, so -9223372036854775808 has to be written as `-9223372036854775807 - 1`".to_string()
+    )]
+  );
+}
+
+#[test]
+fn recover_from_malformed_statements() {
+  let cases = vec![
+    (
+      "let = 1; let y = 2; return ; y",
+      2,
+      vec![
+        node(Stmt::Error),
+        node(Stmt::Let(
+          node(Ident::from("y")),
+          node(Expr::Literal(Literal::Int(2))),
+        )),
+        node(Stmt::Error),
+        node(Stmt::Expr(node(Expr::Ident(Ident::from("y"))))),
+      ],
+    ),
+    (
+      "let x = (1 + 2; x",
+      1,
+      vec![
+        node(Stmt::Error),
+        node(Stmt::Expr(node(Expr::Ident(Ident::from("x"))))),
+      ],
+    ),
+    (
+      "let x = 1 + * 2 let y = 3",
+      1,
+      vec![
+        node(Stmt::Error),
+        node(Stmt::Let(
+          node(Ident::from("y")),
+          node(Expr::Literal(Literal::Int(3))),
+        )),
+      ],
+    ),
+    (
+      "if (x) { let = 1; 2 } 3",
+      1,
+      vec![
+        node(Stmt::Expr(node(Expr::If {
+          condition: Box::new(node(Expr::Ident(Ident::from("x")))),
+          consequence: vec![
+            node(Stmt::Error),
+            node(Stmt::Expr(node(Expr::Literal(Literal::Int(2))))),
+          ],
+          alternative: None,
+        }))),
+        node(Stmt::Expr(node(Expr::Literal(Literal::Int(3))))),
+      ],
+    ),
+    ("fn(x, 1) { x }", 1, vec![node(Stmt::Error)]),
+    (
+      "let h = {1 2}; h",
+      1,
+      vec![
+        node(Stmt::Error),
+        node(Stmt::Expr(node(Expr::Ident(Ident::from("h"))))),
+      ],
+    ),
+    (
+      "if (x) { {1: 2 3: 4}; 5 }",
+      1,
+      vec![node(Stmt::Expr(node(Expr::If {
+        condition: Box::new(node(Expr::Ident(Ident::from("x")))),
+        consequence: vec![
+          node(Stmt::Error),
+          node(Stmt::Expr(node(Expr::Literal(Literal::Int(5))))),
+        ],
+        alternative: None,
+      })))],
+    ),
+    ("if (x) { 1", 1, vec![node(Stmt::Error)]),
+    (
+      "return 1",
+      0,
+      vec![node(Stmt::Return(node(Expr::Literal(Literal::Int(1)))))],
+    ),
+  ];
+
+  for (input, error_count, expected) in cases {
+    let mut parser = Parser::new(Lexer::new(input));
+    let parser = parser.parse();
+
+    assert_eq!(parser.get_errors().len(), error_count, "{}", input);
+    assert_eq!(parser.get_stmts(), expected, "{}", input);
+  }
+}
+
+#[test]
+fn error_node_spans() {
+  let mut parser = Parser::new(Lexer::new("let = 1; 2"));
+  let stmts = parser.parse().get_stmts();
+
+  assert_eq!(stmts[0].span, Span::new(0, 8));
+  assert_eq!(stmts[1].span, Span::new(9, 10));
+}
+
+#[test]
+fn cap_cascading_errors() {
+  let input = ");".repeat(super::MAX_ERRORS + 10);
+  let mut parser = Parser::new(Lexer::new(&input));
+  let errors = parser.parse().get_errors();
+
+  assert_eq!(errors.len(), super::MAX_ERRORS + 1);
+  assert_eq!(
+    errors.last().unwrap().kind(),
+    &ParseErrorKind::TooManyErrors {
+      limit: super::MAX_ERRORS
+    }
+  );
+}
+
+#[test]
+fn error_kinds() {
+  #[cfg_attr(feature = "bigint", allow(unused_mut))]
+  let mut cases = vec![
+    (
+      "let = 1;",
+      ParseErrorKind::UnexpectedToken {
+        expected: vec![Expected::Identifier],
+        found: Token::Assign,
+      },
+      "E0001",
+      Span::new(4, 5),
+    ),
+    (
+      "let x 1;",
+      ParseErrorKind::UnexpectedToken {
+        expected: vec![Expected::Token(Token::Assign)],
+        found: Token::Int(1),
+      },
+      "E0001",
+      Span::new(6, 7),
+    ),
+    (
+      "fn(x, 1) { x }",
+      ParseErrorKind::UnexpectedToken {
+        expected: vec![Expected::Identifier],
+        found: Token::Int(1),
+      },
+      "E0001",
+      Span::new(6, 7),
+    ),
+    (
+      "match (x) { a + 1 => a }",
+      ParseErrorKind::UnexpectedToken {
+        expected: vec![Expected::Token(Token::FatArrow)],
+        found: Token::Plus,
+      },
+      "E0001",
+      Span::new(14, 15),
+    ),
+    (
+      "match (x) { (a) => a }",
+      ParseErrorKind::UnexpectedToken {
+        expected: vec![Expected::Pattern],
+        found: Token::LParen,
+      },
+      "E0001",
+      Span::new(12, 13),
+    ),
+    (
+      "(1 + 2;",
+      ParseErrorKind::MissingDelimiter {
+        delimiter: Token::RParen,
+        open: Span::new(0, 1),
+        found: Token::Semicolon,
+      },
+      "E0002",
+      Span::new(6, 7),
+    ),
+    (
+      "add(1, 2",
+      ParseErrorKind::MissingDelimiter {
+        delimiter: Token::RParen,
+        open: Span::new(3, 4),
+        found: Token::Eof,
+      },
+      "E0002",
+      Span::new(8, 8),
+    ),
+    (
+      "if (x { 1 }",
+      ParseErrorKind::MissingDelimiter {
+        delimiter: Token::RParen,
+        open: Span::new(3, 4),
+        found: Token::LBrace,
+      },
+      "E0002",
+      Span::new(6, 7),
+    ),
+    (
+      "if (x) { 1",
+      ParseErrorKind::UnterminatedBlock {
+        open: Span::new(7, 8),
+      },
+      "E0003",
+      Span::new(10, 10),
+    ),
+    (
+      "1 + 0b102",
+      ParseErrorKind::InvalidIntegerLiteral {
+        literal: "0b102".to_string(),
+      },
+      "E0004",
+      Span::new(4, 9),
+    ),
+    (
+      "1 + 2e",
+      ParseErrorKind::InvalidFloatLiteral {
+        literal: "2e".to_string(),
+      },
+      "E0012",
+      Span::new(4, 6),
+    ),
+    (
+      "1 + ;",
+      ParseErrorKind::NoPrefixParser {
+        found: Token::Semicolon,
+      },
+      "E0005",
+      Span::new(4, 5),
+    ),
+    (
+      "1 + @",
+      ParseErrorKind::IllegalCharacter { found: '@' },
+      "E0006",
+      Span::new(4, 5),
+    ),
+    (
+      "let s = \"abc",
+      ParseErrorKind::UnterminatedString,
+      "E0009",
+      Span::new(8, 12),
+    ),
+    (
+      r#"let s = "a\qb";"#,
+      ParseErrorKind::InvalidEscape {
+        escape: "\\q".to_string(),
+      },
+      "E0010",
+      Span::new(8, 14),
+    ),
+    (
+      "[1, 2",
+      ParseErrorKind::MissingDelimiter {
+        delimiter: Token::RBracket,
+        open: Span::new(0, 1),
+        found: Token::Eof,
+      },
+      "E0002",
+      Span::new(5, 5),
+    ),
+    (
+      "arr[0;",
+      ParseErrorKind::MissingDelimiter {
+        delimiter: Token::RBracket,
+        open: Span::new(3, 4),
+        found: Token::Semicolon,
+      },
+      "E0002",
+      Span::new(5, 6),
+    ),
+    (
+      "[1,]",
+      ParseErrorKind::TrailingComma,
+      "E0007",
+      Span::new(2, 3),
+    ),
+    (
+      "{1 2}",
+      ParseErrorKind::UnexpectedToken {
+        expected: vec![Expected::Token(Token::Colon)],
+        found: Token::Int(2),
+      },
+      "E0001",
+      Span::new(3, 4),
+    ),
+    (
+      "{1: 2 3: 4}",
+      ParseErrorKind::MissingDelimiter {
+        delimiter: Token::RBrace,
+        open: Span::new(0, 1),
+        found: Token::Int(3),
+      },
+      "E0002",
+      Span::new(6, 7),
+    ),
+    (
+      "{1: 2,}",
+      ParseErrorKind::TrailingComma,
+      "E0007",
+      Span::new(5, 6),
+    ),
+    (
+      "let x = 1; /* 2",
+      ParseErrorKind::UnterminatedComment,
+      "E0011",
+      Span::new(11, 15),
+    ),
+    (
+      "add(1, 2,)",
+      ParseErrorKind::TrailingComma,
+      "E0007",
+      Span::new(8, 9),
+    ),
+    (
+      "fn(x,) { x }",
+      ParseErrorKind::TrailingComma,
+      "E0007",
+      Span::new(4, 5),
+    ),
+    (
+      "a + b = 1",
+      ParseErrorKind::InvalidAssignmentTarget,
+      "E0014",
+      Span::new(0, 5),
+    ),
+    (
+      "f()[0] += 1",
+      ParseErrorKind::InvalidAssignmentTarget,
+      "E0014",
+      Span::new(0, 6),
+    ),
+  ];
+  // with the `bigint` feature, large integer literals are valid
+  #[cfg(not(feature = "bigint"))]
+  cases.extend(vec![
+    (
+      "99999999999999999999",
+      ParseErrorKind::IntegerOverflow {
+        literal: "99999999999999999999".to_string(),
+      },
+      "E0013",
+      Span::new(0, 20),
+    ),
+    (
+      "0x1_0000_0000_0000_0000",
+      ParseErrorKind::IntegerOverflow {
+        literal: "0x1_0000_0000_0000_0000".to_string(),
+      },
+      "E0013",
+      Span::new(0, 23),
+    ),
+  ]);
+
+  for (input, kind, code, span) in cases {
+    let mut parser = Parser::new(Lexer::new(input));
+    let errors = parser.parse().get_errors();
+
+    assert_eq!(errors.len(), 1, "{}", input);
+    assert_eq!(errors[0].kind(), &kind, "{}", input);
+    assert_eq!(errors[0].code(), code, "{}", input);
+    assert_eq!(errors[0].span(), span, "{}", input);
+  }
+}
+
+#[test]
+fn error_messages() {
+  #[cfg_attr(feature = "bigint", allow(unused_mut))]
+  let mut cases = vec![
+    ("let = 1;", "error[E0001]: expected identifier, found `=`"),
+    ("(1 + 2", "error[E0002]: expected `)`, found end of input"),
+    ("if (x) { 1", "error[E0003]: unterminated block"),
+    ("1 + ;", "error[E0005]: expected expression, found `;`"),
+    ("add(1,)", "error[E0007]: trailing comma"),
+    (
+      "1 = 2",
+      "error[E0014]: invalid left-hand side of assignment",
+    ),
+  ];
+  #[cfg(not(feature = "bigint"))]
+  cases.push((
+    "let big = 0xffff_ffff_ffff_ffff;",
+    "error[E0013]: integer literal `0xffff_ffff_ffff_ffff` is too large",
+  ));
+
+  for (input, expected) in cases {
+    let mut parser = Parser::new(Lexer::new(input));
+    let errors = parser.parse().get_errors();
+
+    assert_eq!(errors[0].to_string(), expected, "{}", input);
+  }
+}
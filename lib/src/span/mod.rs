@@ -0,0 +1,131 @@
+#[cfg(test)]
+mod test;
+
+use std::fmt;
+
+/// A half-open range `[start, end)` of byte offsets into the source text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Span {
+  pub start: usize,
+  pub end: usize,
+}
+
+impl Span {
+  pub fn new(start: usize, end: usize) -> Self {
+    Span { start, end }
+  }
+
+  /// The smallest span covering both `self` and `other`.
+  pub fn to(self, other: Span) -> Span {
+    Span {
+      start: self.start.min(other.start),
+      end: self.end.max(other.end),
+    }
+  }
+
+  pub fn len(&self) -> usize {
+    self.end - self.start
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.start == self.end
+  }
+}
+
+impl fmt::Display for Span {
+  fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+    write!(formatter, "{}..{}", self.start, self.end)
+  }
+}
+
+/// A node of type `T` along with the source range it was read from.
+///
+/// Spans are location metadata rather than part of the value, so two spanned
+/// nodes are equal as long as their nodes are, wherever they come from.
+#[derive(Debug, Clone)]
+pub struct Spanned<T> {
+  pub node: T,
+  pub span: Span,
+}
+
+impl<T> Spanned<T> {
+  pub fn new(node: T, span: Span) -> Self {
+    Spanned { node, span }
+  }
+}
+
+/// Wrap a node that doesn't come from any source, e.g. a synthesized one.
+impl<T> From<T> for Spanned<T> {
+  fn from(node: T) -> Self {
+    Spanned::new(node, Span::default())
+  }
+}
+
+impl<T: PartialEq> PartialEq for Spanned<T> {
+  fn eq(&self, other: &Self) -> bool {
+    self.node == other.node
+  }
+}
+
+/// A human readable location, both line and column are 1-based. Columns count
+/// characters rather than bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Position {
+  pub line: usize,
+  pub column: usize,
+}
+
+impl fmt::Display for Position {
+  fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+    write!(formatter, "{}:{}", self.line, self.column)
+  }
+}
+
+/// Resolve byte offsets of a source text into lines and columns.
+pub struct LineIndex<'source> {
+  source: &'source str,
+  /// byte offset of the first character of every line
+  line_starts: Vec<usize>,
+}
+
+impl<'source> LineIndex<'source> {
+  pub fn new(source: &'source str) -> Self {
+    let line_starts = std::iter::once(0)
+      .chain(source.match_indices('\n').map(|(offset, _)| offset + 1))
+      .collect();
+    LineIndex {
+      source,
+      line_starts,
+    }
+  }
+
+  /// Position of the character at `offset`, offsets past the end of the source
+  /// resolve to the end of the last line.
+  pub fn position(&self, offset: usize) -> Position {
+    let offset = offset.min(self.source.len());
+    let line = match self.line_starts.binary_search(&offset) {
+      Ok(line) => line,
+      Err(next_line) => next_line - 1,
+    };
+    let line_start = self.line_starts[line];
+    let column = self.source[line_start..offset].chars().count() + 1;
+    Position {
+      line: line + 1,
+      column,
+    }
+  }
+
+  /// The text of the 1-based `line`, without its line terminator.
+  pub fn line(&self, line: usize) -> Option<&'source str> {
+    let start = *self.line_starts.get(line.checked_sub(1)?)?;
+    let end = self
+      .line_starts
+      .get(line)
+      .map_or(self.source.len(), |next_start| next_start - 1);
+    Some(self.source[start..end].trim_end_matches('\r'))
+  }
+
+  pub fn line_count(&self) -> usize {
+    self.line_starts.len()
+  }
+}
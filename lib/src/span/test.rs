@@ -0,0 +1,63 @@
+use super::{LineIndex, Position, Span, Spanned};
+
+#[test]
+fn merge_spans() {
+  assert_eq!(Span::new(2, 4).to(Span::new(6, 9)), Span::new(2, 9));
+  assert_eq!(Span::new(6, 9).to(Span::new(2, 4)), Span::new(2, 9));
+  assert_eq!(Span::new(2, 9).to(Span::new(3, 4)), Span::new(2, 9));
+}
+
+#[test]
+fn spanned_equality_ignores_span() {
+  assert_eq!(
+    Spanned::new(1, Span::new(0, 1)),
+    Spanned::new(1, Span::new(4, 5))
+  );
+  assert_ne!(
+    Spanned::new(1, Span::new(0, 1)),
+    Spanned::new(2, Span::new(0, 1))
+  );
+}
+
+#[test]
+fn resolve_positions() {
+  let index = LineIndex::new("let a = 1;\nlet b = 2;\r\n\nb");
+  let cases = vec![
+    (0, Position { line: 1, column: 1 }),
+    (4, Position { line: 1, column: 5 }),
+    (
+      10,
+      Position {
+        line: 1,
+        column: 11,
+      },
+    ),
+    (11, Position { line: 2, column: 1 }),
+    (15, Position { line: 2, column: 5 }),
+    (23, Position { line: 3, column: 1 }),
+    (24, Position { line: 4, column: 1 }),
+    (100, Position { line: 4, column: 2 }),
+  ];
+
+  for (offset, expected) in cases {
+    assert_eq!(index.position(offset), expected);
+  }
+}
+
+#[test]
+fn count_columns_in_chars() {
+  let index = LineIndex::new("a ≤ b");
+  assert_eq!(index.position(6), Position { line: 1, column: 5 });
+}
+
+#[test]
+fn read_lines() {
+  let index = LineIndex::new("let a = 1;\nlet b = 2;\r\n\nb");
+  assert_eq!(index.line_count(), 4);
+  assert_eq!(index.line(1), Some("let a = 1;"));
+  assert_eq!(index.line(2), Some("let b = 2;"));
+  assert_eq!(index.line(3), Some(""));
+  assert_eq!(index.line(4), Some("b"));
+  assert_eq!(index.line(0), None);
+  assert_eq!(index.line(5), None);
+}
@@ -1,48 +1,116 @@
+use crate::span::{Span, Spanned};
+use std::fmt;
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum Token<'input> {
-  Illegal,
+  /// A piece of input the lexer couldn't make a token out of.
+  Illegal(LexError),
   Eof,
 
   // identifier + literal
   Ident(&'input str),
   Int(i64),
+  /// An integer literal which doesn't fit into an `Int`.
+  #[cfg(feature = "bigint")]
+  BigInt(num_bigint::BigInt),
+  Float(f64),
   Bool(bool),
+  /// A string literal, with its escape sequences already resolved.
+  Str(String),
 
   // statements
   If,
   Else,
+  While,
+  Break,
+  Continue,
+  For,
+  In,
+  Match,
 
   // operators
   /// `=`
   Assign,
+  /// `+=`
+  PlusAssign,
+  /// `-=`
+  MinusAssign,
+  /// `*=` or `×=`
+  AsteriskAssign,
+  /// `/=` or `÷=`
+  SlashAssign,
+  /// `%=`
+  PercentAssign,
+  /// `**=`
+  PowerAssign,
+  /// `&=`
+  AmpersandAssign,
+  /// `|=`
+  PipeAssign,
+  /// `^=`
+  CaretAssign,
+  /// `<<=`
+  ShiftLeftAssign,
+  /// `>>=`
+  ShiftRightAssign,
   /// `+`
   Plus,
   /// `-`
   Minus,
   /// `!`
   Bang,
-  /// `*`
+  /// `*` or `×`
   Asterisk,
-  /// `/`
+  /// `**`
+  Power,
+  /// `%`
+  Percent,
+  /// `&`
+  Ampersand,
+  /// `|`
+  Pipe,
+  /// `^`
+  Caret,
+  /// `~`
+  Tilde,
+  /// `<<`
+  ShiftLeft,
+  /// `>>`
+  ShiftRight,
+  /// `/` or `÷`
   Slash,
   /// `<`
   LessThan,
   /// `>`
   GreaterThan,
-  /// `=`
+  /// `==`
   Equal,
-  /// `≠`
+  /// `!=` or `≠`
   NotEqual,
-  /// `≤`
+  /// `<=` or `≤`
   LessThanEqual,
-  /// `≥`
+  /// `>=` or `≥`
   GreaterThanEqual,
+  /// `&&`
+  And,
+  /// `||`
+  Or,
+  /// `?`
+  Question,
+  /// `..`
+  DotDot,
+  /// `..=`
+  DotDotEqual,
 
   // delimiters
   /// ,
   Comma,
   /// ;
   Semicolon,
+  /// :
+  Colon,
+  /// =>
+  FatArrow,
   /// (
   LParen,
   /// )
@@ -51,13 +119,144 @@ pub enum Token<'input> {
   LBrace,
   /// }
   RBrace,
+  /// [
+  LBracket,
+  /// ]
+  RBracket,
 
   // reserved words
   Function,
   Let,
+  Const,
   Return,
 }
 
-const CHAR_NUL: &str = "\u{0000}";
+/// Why the lexer rejected a piece of input.
+#[derive(Debug, Clone, PartialEq)]
+pub enum LexError {
+  /// A character which can't start any token.
+  IllegalCharacter(char),
+  /// A malformed integer literal, e.g. `0x` or `0b12`.
+  InvalidInteger(String),
+  /// An integer literal which is too large for an integer.
+  IntegerOverflow(String),
+  /// A float literal which is malformed, e.g. `1e`, or overflows.
+  InvalidFloat(String),
+  /// A string literal which isn't closed before the end of the input.
+  UnterminatedString,
+  /// An unknown or malformed escape sequence in a string literal, e.g. `\q`.
+  InvalidEscape(String),
+  /// A block comment which isn't closed before the end of the input.
+  UnterminatedComment,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TriviaKind {
+  /// `// ...`
+  LineComment,
+  /// `/// ...`
+  DocComment,
+  /// `/* ... */`, which may nest.
+  BlockComment,
+}
+
+/// A comment. The parser never sees them, but they can be retained by the
+/// lexer for tools which have to reproduce the source, e.g. formatters.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Trivia<'input> {
+  pub kind: TriviaKind,
+  /// The comment as it's written in the source, delimiters included.
+  pub text: &'input str,
+  pub span: Span,
+}
+
+/// A token along with the comments between it and the previous token.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TokenWithTrivia<'input> {
+  pub leading_trivia: Vec<Trivia<'input>>,
+  pub token: Spanned<Token<'input>>,
+}
+
+// implement fmt.Display trait for Token type, it prints the token as it's
+// written in the source.
+impl fmt::Display for Token<'_> {
+  fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      Token::Illegal(LexError::IllegalCharacter(ch)) => write!(formatter, "{}", ch),
+      Token::Illegal(LexError::InvalidInteger(literal)) => write!(formatter, "{}", literal),
+      Token::Illegal(LexError::IntegerOverflow(literal)) => write!(formatter, "{}", literal),
+      Token::Illegal(LexError::InvalidFloat(literal)) => write!(formatter, "{}", literal),
+      Token::Illegal(LexError::UnterminatedString) => write!(formatter, "\""),
+      Token::Illegal(LexError::InvalidEscape(escape)) => write!(formatter, "{}", escape),
+      Token::Illegal(LexError::UnterminatedComment) => write!(formatter, "/*"),
+      Token::Eof => Ok(()),
+      Token::Ident(ident) => write!(formatter, "{}", ident),
+      Token::Int(val) => write!(formatter, "{}", val),
+      #[cfg(feature = "bigint")]
+      Token::BigInt(val) => write!(formatter, "{}", val),
+      Token::Float(val) => write!(formatter, "{:?}", val),
+      Token::Bool(val) => write!(formatter, "{}", val),
+      Token::Str(val) => write!(formatter, "{:?}", val),
+      Token::If => write!(formatter, "if"),
+      Token::Else => write!(formatter, "else"),
+      Token::While => write!(formatter, "while"),
+      Token::Break => write!(formatter, "break"),
+      Token::Continue => write!(formatter, "continue"),
+      Token::For => write!(formatter, "for"),
+      Token::In => write!(formatter, "in"),
+      Token::Match => write!(formatter, "match"),
+      Token::Assign => write!(formatter, "="),
+      Token::PlusAssign => write!(formatter, "+="),
+      Token::MinusAssign => write!(formatter, "-="),
+      Token::AsteriskAssign => write!(formatter, "*="),
+      Token::SlashAssign => write!(formatter, "/="),
+      Token::PercentAssign => write!(formatter, "%="),
+      Token::PowerAssign => write!(formatter, "**="),
+      Token::AmpersandAssign => write!(formatter, "&="),
+      Token::PipeAssign => write!(formatter, "|="),
+      Token::CaretAssign => write!(formatter, "^="),
+      Token::ShiftLeftAssign => write!(formatter, "<<="),
+      Token::ShiftRightAssign => write!(formatter, ">>="),
+      Token::Plus => write!(formatter, "+"),
+      Token::Minus => write!(formatter, "-"),
+      Token::Bang => write!(formatter, "!"),
+      Token::Asterisk => write!(formatter, "*"),
+      Token::Power => write!(formatter, "**"),
+      Token::Percent => write!(formatter, "%"),
+      Token::Ampersand => write!(formatter, "&"),
+      Token::Pipe => write!(formatter, "|"),
+      Token::Caret => write!(formatter, "^"),
+      Token::Tilde => write!(formatter, "~"),
+      Token::ShiftLeft => write!(formatter, "<<"),
+      Token::ShiftRight => write!(formatter, ">>"),
+      Token::Slash => write!(formatter, "/"),
+      Token::LessThan => write!(formatter, "<"),
+      Token::GreaterThan => write!(formatter, ">"),
+      Token::Equal => write!(formatter, "=="),
+      Token::NotEqual => write!(formatter, "!="),
+      Token::LessThanEqual => write!(formatter, "<="),
+      Token::GreaterThanEqual => write!(formatter, ">="),
+      Token::And => write!(formatter, "&&"),
+      Token::Or => write!(formatter, "||"),
+      Token::Question => write!(formatter, "?"),
+      Token::DotDot => write!(formatter, ".."),
+      Token::DotDotEqual => write!(formatter, "..="),
+      Token::Comma => write!(formatter, ","),
+      Token::Semicolon => write!(formatter, ";"),
+      Token::Colon => write!(formatter, ":"),
+      Token::FatArrow => write!(formatter, "=>"),
+      Token::LParen => write!(formatter, "("),
+      Token::RParen => write!(formatter, ")"),
+      Token::LBrace => write!(formatter, "{{"),
+      Token::RBrace => write!(formatter, "}}"),
+      Token::LBracket => write!(formatter, "["),
+      Token::RBracket => write!(formatter, "]"),
+      Token::Function => write!(formatter, "fn"),
+      Token::Let => write!(formatter, "let"),
+      Token::Const => write!(formatter, "const"),
+      Token::Return => write!(formatter, "return"),
+    }
+  }
+}
 
-pub const CHAR_NUL_BYTE: u8 = CHAR_NUL.as_bytes()[0];
+pub const CHAR_NUL: char = '\u{0000}';